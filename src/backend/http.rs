@@ -0,0 +1,68 @@
+use anyhow::Result;
+
+use super::Backend;
+use crate::products::ProductKind;
+use crate::roblox::{OpenCloud, Product, RemoteEntry};
+
+/// Backend for the Open Cloud APIs of a single universe
+pub struct HttpBackend {
+    universe_id: i64,
+    api: OpenCloud,
+}
+
+impl HttpBackend {
+    pub fn new(universe_id: i64, api: OpenCloud) -> Self {
+        HttpBackend { universe_id, api }
+    }
+}
+
+impl Backend for HttpBackend {
+    fn create(&self, kind: ProductKind, data: &Product) -> Result<RemoteEntry> {
+        Ok(match kind {
+            ProductKind::Product => self.api.create_product(self.universe_id, data)?.into(),
+            ProductKind::Gamepass => self.api.create_gamepass(self.universe_id, data)?.into(),
+        })
+    }
+
+    fn update(&self, kind: ProductKind, id: i64, data: &Product) -> Result<RemoteEntry> {
+        Ok(match kind {
+            ProductKind::Product => self.api.update_product(self.universe_id, id, data)?.into(),
+            ProductKind::Gamepass => self.api.update_gamepass(self.universe_id, id, data)?.into(),
+        })
+    }
+
+    fn get(&self, kind: ProductKind, id: i64) -> Result<RemoteEntry> {
+        Ok(match kind {
+            ProductKind::Product => self.api.get_product(self.universe_id, id)?.into(),
+            ProductKind::Gamepass => self.api.get_gamepass(self.universe_id, id)?.into(),
+        })
+    }
+
+    fn list(&self, kind: ProductKind) -> Result<Vec<RemoteEntry>> {
+        Ok(match kind {
+            ProductKind::Product => self
+                .api
+                .list_products(self.universe_id)?
+                .into_iter()
+                .map(RemoteEntry::from)
+                .collect(),
+            ProductKind::Gamepass => self
+                .api
+                .list_gamepasses(self.universe_id)?
+                .into_iter()
+                .map(RemoteEntry::from)
+                .collect(),
+        })
+    }
+
+    fn set_for_sale(&self, kind: ProductKind, id: i64, for_sale: bool) -> Result<()> {
+        match kind {
+            ProductKind::Product => self
+                .api
+                .set_product_for_sale(self.universe_id, id, for_sale),
+            ProductKind::Gamepass => self
+                .api
+                .set_gamepass_for_sale(self.universe_id, id, for_sale),
+        }
+    }
+}
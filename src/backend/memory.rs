@@ -0,0 +1,144 @@
+use anyhow::Result;
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+
+use super::Backend;
+use crate::products::ProductKind;
+use crate::roblox::{ApiError, Product, RemoteEntry};
+
+/// Asset IDs handed out for uploaded icons start here so they are easy to tell apart
+const FIRST_ASSET_ID: i64 = 1_000_000;
+
+#[derive(Deserialize, Serialize, Debug, Default, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MemoryState {
+    next_id: i64,
+    next_asset_id: i64,
+    products: BTreeMap<i64, RemoteEntry>,
+    game_passes: BTreeMap<i64, RemoteEntry>,
+}
+
+impl MemoryState {
+    fn entries(&mut self, kind: ProductKind) -> &mut BTreeMap<i64, RemoteEntry> {
+        match kind {
+            ProductKind::Product => &mut self.products,
+            ProductKind::Gamepass => &mut self.game_passes,
+        }
+    }
+
+    fn take_id(&mut self) -> i64 {
+        self.next_id = self.next_id.max(1);
+        let id = self.next_id;
+        self.next_id += 1;
+        id
+    }
+
+    fn take_asset_id(&mut self) -> i64 {
+        self.next_asset_id = self.next_asset_id.max(FIRST_ASSET_ID);
+        let id = self.next_asset_id;
+        self.next_asset_id += 1;
+        id
+    }
+}
+
+fn not_found(kind: ProductKind, id: i64) -> anyhow::Error {
+    ApiError {
+        status: StatusCode::NOT_FOUND,
+        message: format!("{} {}", kind.as_str(), id),
+    }
+    .into()
+}
+
+/// In-memory fake of a universe, for tests and `jaxon mock-server`.
+/// Uploaded images are not read, any non-empty `image_file` gets a new asset ID.
+#[derive(Default)]
+pub struct MemoryBackend {
+    state: Mutex<MemoryState>,
+}
+
+impl MemoryBackend {
+    pub fn from_state(state: MemoryState) -> Self {
+        MemoryBackend {
+            state: Mutex::new(state),
+        }
+    }
+
+    pub fn state(&self) -> MemoryState {
+        self.state.lock().unwrap().clone()
+    }
+}
+
+impl Backend for MemoryBackend {
+    fn create(&self, kind: ProductKind, data: &Product) -> Result<RemoteEntry> {
+        if data.name.is_empty() {
+            return Err(ApiError {
+                status: StatusCode::BAD_REQUEST,
+                message: "name is required".into(),
+            }
+            .into());
+        }
+
+        let mut state = self.state.lock().unwrap();
+        let id = state.take_id();
+        let icon_asset_id = (!data.image_file.is_empty()).then(|| state.take_asset_id());
+
+        let entry = RemoteEntry {
+            kind,
+            id,
+            name: data.name.clone(),
+            description: data.description.clone(),
+            price: data.price,
+            regional_pricing: data.regional_pricing,
+            for_sale: data.for_sale,
+            icon_asset_id,
+        };
+        state.entries(kind).insert(id, entry.clone());
+        Ok(entry)
+    }
+
+    fn update(&self, kind: ProductKind, id: i64, data: &Product) -> Result<RemoteEntry> {
+        let mut state = self.state.lock().unwrap();
+        let icon_asset_id = (!data.image_file.is_empty()).then(|| state.take_asset_id());
+
+        let Some(entry) = state.entries(kind).get_mut(&id) else {
+            return Err(not_found(kind, id));
+        };
+        entry.name = data.name.clone();
+        entry.description = data.description.clone();
+        if data.price.is_some() {
+            entry.price = data.price;
+        }
+        entry.regional_pricing = data.regional_pricing;
+        entry.for_sale = data.for_sale;
+        if icon_asset_id.is_some() {
+            entry.icon_asset_id = icon_asset_id;
+        }
+        Ok(entry.clone())
+    }
+
+    fn get(&self, kind: ProductKind, id: i64) -> Result<RemoteEntry> {
+        let mut state = self.state.lock().unwrap();
+        match state.entries(kind).get(&id) {
+            Some(entry) => Ok(entry.clone()),
+            None => Err(not_found(kind, id)),
+        }
+    }
+
+    fn list(&self, kind: ProductKind) -> Result<Vec<RemoteEntry>> {
+        let mut state = self.state.lock().unwrap();
+        Ok(state.entries(kind).values().cloned().collect())
+    }
+
+    fn set_for_sale(&self, kind: ProductKind, id: i64, for_sale: bool) -> Result<()> {
+        let mut state = self.state.lock().unwrap();
+        match state.entries(kind).get_mut(&id) {
+            Some(entry) => {
+                entry.for_sale = for_sale;
+                Ok(())
+            }
+            None => Err(not_found(kind, id)),
+        }
+    }
+}
@@ -0,0 +1,26 @@
+mod http;
+mod memory;
+
+use anyhow::Result;
+
+use crate::products::ProductKind;
+use crate::roblox::{Product, RemoteEntry};
+
+pub use http::HttpBackend;
+pub use memory::{MemoryBackend, MemoryState};
+
+/// Where developer products and game passes live. The sync engine only talks to
+/// this trait, so it can run against Roblox or against an in-memory fake.
+pub trait Backend: Send + Sync {
+    fn create(&self, kind: ProductKind, data: &Product) -> Result<RemoteEntry>;
+
+    /// Replaces the entry's fields and returns its new state. The icon is only
+    /// replaced when `data.image_file` is set.
+    fn update(&self, kind: ProductKind, id: i64, data: &Product) -> Result<RemoteEntry>;
+
+    fn get(&self, kind: ProductKind, id: i64) -> Result<RemoteEntry>;
+
+    fn list(&self, kind: ProductKind) -> Result<Vec<RemoteEntry>>;
+
+    fn set_for_sale(&self, kind: ProductKind, id: i64, for_sale: bool) -> Result<()>;
+}
@@ -1,4 +1,75 @@
-use clap::{Parser, Subcommand};
+use clap::{Parser, Subcommand, ValueEnum};
+use std::path::PathBuf;
+
+#[derive(clap::Args, Debug)]
+pub struct SyncArgs {
+    /// Print what would be created, updated or skipped without changing anything
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// Take entries removed from products.json off sale
+    #[arg(long)]
+    pub prune: bool,
+
+    /// Don't ask for confirmation before pruning
+    #[arg(short, long)]
+    pub yes: bool,
+
+    /// Recover the results of a sync that was interrupted, then finish it
+    #[arg(long)]
+    pub resume: bool,
+
+    /// Number of entries synced in parallel [default: from jaxon.toml]
+    #[arg(long)]
+    pub workers: Option<usize>,
+
+    /// Maximum requests per second [default: from jaxon.toml]
+    #[arg(long)]
+    pub rate_limit: Option<f64>,
+}
+
+#[derive(clap::Args, Debug)]
+pub struct SchemaArgs {
+    /// Directory to write the schema files to
+    #[arg(short, long, default_value = ".")]
+    pub output: PathBuf,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+pub enum ExportFormat {
+    Json,
+    Csv,
+}
+
+#[derive(clap::Args, Debug)]
+pub struct ExportArgs {
+    /// Format of the manifest
+    #[arg(short, long, value_enum, default_value = "json")]
+    pub format: ExportFormat,
+
+    /// File to write instead of printing to stdout
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(clap::Args, Debug)]
+pub struct MockServerArgs {
+    /// Port to listen on
+    #[arg(short, long, default_value_t = 8080)]
+    pub port: u16,
+
+    /// JSON file to keep the state in between runs
+    #[arg(long)]
+    pub state: Option<PathBuf>,
+
+    /// Answer every Nth request with 429
+    #[arg(long)]
+    pub rate_limit_every: Option<u64>,
+
+    /// Answer every Nth request with 503
+    #[arg(long)]
+    pub fail_every: Option<u64>,
+}
 
 #[derive(Parser, Debug)]
 #[command(name = "jaxon")]
@@ -7,6 +78,14 @@ use clap::{Parser, Subcommand};
     long_about = "jaxon is a command-line tool for creating, syncing, and managing developer products and game passes."
 )]
 pub struct Args {
+    /// Directory of the project, or its config file, to run in [default: current directory]
+    #[arg(long, global = true)]
+    pub project: Option<PathBuf>,
+
+    /// Environment from jaxon.toml to work on, e.g. dev or prod
+    #[arg(long, global = true)]
+    pub env: Option<String>,
+
     #[command(subcommand)]
     pub command: Command,
 }
@@ -21,6 +100,7 @@ to operate. By default, it generates:
 
   - jaxon.toml       Project configuration file
   - products.json    Initial product definition file
+  - *.schema.json    JSON Schemas both files reference for editor support
 
 You can use the --minimal flag to generate only the configuration file
 and skip creating product-related files.")]
@@ -32,7 +112,104 @@ and skip creating product-related files.")]
 
     /// Sync local product definitions to Roblox
     #[command(
-        long_about = "Synchronize products defined in the local products.json file\nwith Roblox developer products and game passes."
+        long_about = "Synchronize products defined in the local products.json file
+with Roblox developer products and game passes.
+
+Use --dry-run to print the plan without changing anything. Each entry is
+listed as create, update (with the fields that differ from the live
+state), skip (unchanged since the last sync), invalid, or missing when it
+was deleted on Roblox. A sync without
+--dry-run stops before sending anything when an entry is invalid.
+
+Use --prune to take entries that are tracked in jaxon.lock but were
+removed from products.json off sale. You are asked to confirm first,
+unless --yes is given.
+
+Every entry is written to jaxon.journal as soon as Roblox confirms it.
+If a sync is killed before it saves products.json and jaxon.lock, run
+it again with --resume to recover the created IDs and finish the rest.
+Ctrl-C stops after the requests in flight and saves what was synced."
+    )]
+    Sync(SyncArgs),
+
+    /// Import existing developer products and game passes from Roblox
+    #[command(
+        alias = "import",
+        long_about = "Import the developer products and game passes that already exist in the universe.
+
+Entries are merged into products.json by productId, or by name and type
+for entries that have not been synced yet. New entries are appended with
+an empty image. jaxon.lock is seeded with the pulled entries so the next
+sync does not push them again."
+    )]
+    Pull,
+
+    /// Generate code from products.json without contacting Roblox
+    #[command(
+        long_about = "Generate the Luau (and optionally TypeScript) module from the whole products.json.
+
+IDs come from products.json and icon asset IDs from jaxon.lock, so no
+network access or API key is needed. Entries without a productId have
+not been synced yet and are left out."
+    )]
+    Generate,
+
+    /// Compare tracked entries with their live state on Roblox
+    #[command(
+        alias = "diff",
+        long_about = "Compare every tracked entry in products.json with its live state on Roblox.
+
+Changes made outside of jaxon, such as prices edited in the Creator
+Dashboard, are printed field by field, and entries deleted there are
+reported as missing. The command exits with a non-zero status when any
+entry has drifted, so it can be used as a CI check."
+    )]
+    Status,
+
+    /// Validate products.json without contacting Roblox
+    #[command(long_about = "Validate products.json without contacting Roblox.
+
+Checks name and description lengths, prices, types, duplicate names,
+keys and productIds, and that every image exists, is a PNG, JPEG, BMP or
+TGA file and is within the size limit. Every problem is printed with the
+entry's index and field, and the command exits with a non-zero status
+when there is any. sync runs the same checks before sending anything.")]
+    Check,
+
+    /// Export the synced catalog as JSON or CSV
+    #[command(
+        long_about = "Export a manifest of the synced catalog for backends and analytics.
+
+Every entry is listed with its key, kind, ID, name, description, price,
+sale state, icon asset ID, tags and metadata, resolved the same way as
+generate, so --env exports the IDs of that environment. JSON groups the
+entries into Products and Gamepasses keyed by entry key, CSV has one row
+per entry. The manifest is printed to stdout unless --output is given."
+    )]
+    Export(ExportArgs),
+
+    /// Write JSON Schemas for products.json and jaxon.toml
+    #[command(
+        long_about = "Write JSON Schemas for products.json and jaxon.toml, so editors can
+autocomplete and validate them as you type.
+
+Writes products.schema.json and jaxon.schema.json. Reference them from
+products.json with a \"$schema\" key, which needs the object layout
+{ \"$schema\": \"./products.schema.json\", \"products\": [...] }, and from
+jaxon.toml with a `#:schema ./jaxon.schema.json` comment on the first
+line (supported by Taplo / Even Better TOML). jaxon init does both."
+    )]
+    Schema(SchemaArgs),
+
+    /// Run a local stand-in for the Open Cloud product APIs
+    #[command(
+        long_about = "Run a local server that behaves like the developer-products v2 and
+game-passes v1 Open Cloud endpoints: create, update, creator info and listing.
+
+Point jaxon at it with JAXON_API_URL=http://127.0.0.1:8080 (or api_url in
+jaxon.toml) to try workflows without touching a real universe. Any API key
+is accepted. State is kept in memory, or in the --state file when given.
+Rate limits and server errors can be injected to exercise retries."
     )]
-    Sync,
+    MockServer(MockServerArgs),
 }
@@ -1,68 +1,337 @@
 use anyhow::{Context, Result};
-use std::collections::HashMap;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
 use std::path::Path;
 
-use crate::config::{Files, Generation};
+use crate::config::{Config, Field, Output, OutputFormat};
+use crate::lock::LockEntry;
+use crate::products::{ProductJson, ProductKind};
+use crate::template;
 
-#[derive(Clone)]
+/// An entry as seen by the generators and templates
+#[derive(Serialize, Clone)]
 pub struct ProductCodeMap {
+    pub key: String,
     pub name: String,
+    pub description: String,
+    pub kind: ProductKind,
     pub id: i64,
+    /// 0 for entries without a price
+    pub price: i64,
+    pub regional_pricing: bool,
+    pub for_sale: bool,
+    /// Icon asset ID from the lock, 0 when unknown
+    pub icon_asset_id: i64,
+    /// `rbxassetid://` URL of the icon
     pub image: String,
+    pub tags: Vec<String>,
+    pub metadata: BTreeMap<String, serde_json::Value>,
 }
 
-fn generate_luau(
-    products: &HashMap<i64, ProductCodeMap>,
-    passes: &HashMap<i64, ProductCodeMap>,
-) -> String {
-    let mut out = String::new();
+/// Builds the codegen tables from the whole catalog, using the lock for
+/// remote data such as icon asset IDs. Entries that were never synced are left out.
+/// Both tables are sorted by key, so the output only changes with the catalog.
+pub fn build_code_maps(
+    products: &[ProductJson],
+    locked: &HashMap<i64, LockEntry>,
+) -> (Vec<ProductCodeMap>, Vec<ProductCodeMap>) {
+    let mut products_map = Vec::new();
+    let mut passes_map = Vec::new();
+
+    for product in products {
+        if product.id <= 0 {
+            continue;
+        }
+
+        let (map, kind) = match product.kind() {
+            Some(kind @ ProductKind::Product) => (&mut products_map, kind),
+            Some(kind @ ProductKind::Gamepass) => (&mut passes_map, kind),
+            None => continue,
+        };
+
+        let icon_asset_id = locked
+            .get(&product.id)
+            .and_then(|entry| entry.icon_asset_id)
+            .unwrap_or(0);
+
+        map.push(ProductCodeMap {
+            key: product.key().to_string(),
+            name: product.name.clone(),
+            description: product.description.clone(),
+            kind,
+            id: product.id,
+            price: product.price.unwrap_or(0),
+            regional_pricing: product.regional_pricing,
+            for_sale: product.for_sale,
+            icon_asset_id,
+            image: format!("rbxassetid://{}", icon_asset_id),
+            tags: product.tags.clone(),
+            metadata: product.metadata.clone(),
+        });
+    }
 
-    out.push_str("-- Autogenerated by jaxon CLI\n");
-    out.push_str("local Products = {\n");
-    out.push_str("\tProducts = {\n");
+    products_map.sort_by(|a, b| a.key.cmp(&b.key).then(a.id.cmp(&b.id)));
+    passes_map.sort_by(|a, b| a.key.cmp(&b.key).then(a.id.cmp(&b.id)));
+    (products_map, passes_map)
+}
 
-    for product in products.values() {
+/// Quotes `value` as a Luau string literal
+pub fn luau_string(value: &str) -> String {
+    let mut out = String::with_capacity(value.len() + 2);
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c.is_control() => out.push_str(&format!("\\u{{{:x}}}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// Quotes `value` as a TypeScript string literal
+pub fn ts_string(value: &str) -> String {
+    // JSON strings are valid TypeScript string literals
+    serde_json::to_string(value).unwrap_or_else(|_| "\"\"".into())
+}
+
+/// String-literal union of the keys, or `never` when there are none
+fn luau_union(items: &[ProductCodeMap]) -> String {
+    if items.is_empty() {
+        return "never".into();
+    }
+
+    items
+        .iter()
+        .map(|item| luau_string(&item.key))
+        .collect::<Vec<_>>()
+        .join(" | ")
+}
+
+/// Writes a metadata value as a Luau expression
+fn luau_value(value: &serde_json::Value) -> String {
+    use serde_json::Value;
+
+    match value {
+        Value::Null => "nil".into(),
+        Value::Bool(b) => b.to_string(),
+        Value::Number(n) => n.to_string(),
+        Value::String(s) => luau_string(s),
+        Value::Array(items) if items.is_empty() => "{}".into(),
+        Value::Array(items) => format!(
+            "{{ {} }}",
+            items.iter().map(luau_value).collect::<Vec<_>>().join(", ")
+        ),
+        Value::Object(fields) if fields.is_empty() => "{}".into(),
+        Value::Object(fields) => format!(
+            "{{ {} }}",
+            fields
+                .iter()
+                .map(|(k, v)| format!("[{}] = {}", luau_string(k), luau_value(v)))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    }
+}
+
+/// Writes a metadata value as a TypeScript literal type
+fn ts_type(value: &serde_json::Value) -> String {
+    use serde_json::Value;
+
+    match value {
+        Value::Null => "null".into(),
+        Value::Bool(b) => b.to_string(),
+        Value::Number(n) => n.to_string(),
+        Value::String(s) => ts_string(s),
+        Value::Array(items) => format!(
+            "readonly [{}]",
+            items.iter().map(ts_type).collect::<Vec<_>>().join(", ")
+        ),
+        Value::Object(fields) if fields.is_empty() => "{}".into(),
+        Value::Object(fields) => format!(
+            "{{ {} }}",
+            fields
+                .iter()
+                .map(|(k, v)| format!("{}: {};", ts_string(k), ts_type(v)))
+                .collect::<Vec<_>>()
+                .join(" ")
+        ),
+    }
+}
+
+/// Writes a metadata value as a TypeScript expression
+fn ts_value(value: &serde_json::Value) -> String {
+    use serde_json::Value;
+
+    match value {
+        Value::Array(items) => format!(
+            "[{}]",
+            items.iter().map(ts_value).collect::<Vec<_>>().join(", ")
+        ),
+        Value::Object(fields) if fields.is_empty() => "{}".into(),
+        Value::Object(fields) => format!(
+            "{{ {} }}",
+            fields
+                .iter()
+                .map(|(k, v)| format!("{}: {}", ts_string(k), ts_value(v)))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        // Scalars are written the same way as their literal types
+        value => ts_type(value),
+    }
+}
+
+/// Name of the field in generated code
+fn field_name(field: Field) -> &'static str {
+    match field {
+        Field::Name => "Name",
+        Field::Description => "Description",
+        Field::Kind => "Kind",
+        Field::Id => "Id",
+        Field::Price => "Price",
+        Field::RegionalPricing => "RegionalPricing",
+        Field::ForSale => "ForSale",
+        Field::Image => "Image",
+        Field::IconAssetId => "IconAssetId",
+        Field::Tags => "Tags",
+        Field::Metadata => "Metadata",
+    }
+}
+
+fn luau_field_type(field: Field) -> &'static str {
+    match field {
+        Field::Name | Field::Description | Field::Image => "string",
+        Field::Kind => "\"Product\" | \"Gamepass\"",
+        Field::Id | Field::Price | Field::IconAssetId => "number",
+        Field::RegionalPricing | Field::ForSale => "boolean",
+        Field::Tags => "{ string }",
+        Field::Metadata => "{ [string]: any }",
+    }
+}
+
+fn field_value(item: &ProductCodeMap, field: Field) -> serde_json::Value {
+    use serde_json::Value;
+
+    match field {
+        Field::Name => Value::from(item.name.as_str()),
+        Field::Description => Value::from(item.description.as_str()),
+        Field::Kind => Value::from(item.kind.as_str()),
+        Field::Id => Value::from(item.id),
+        Field::Price => Value::from(item.price),
+        Field::RegionalPricing => Value::from(item.regional_pricing),
+        Field::ForSale => Value::from(item.for_sale),
+        Field::Image => Value::from(item.image.as_str()),
+        Field::IconAssetId => Value::from(item.icon_asset_id),
+        Field::Tags => Value::from(item.tags.clone()),
+        Field::Metadata => Value::Object(item.metadata.clone().into_iter().collect()),
+    }
+}
+
+/// Writes an entry as a table or object, one field per line, with `line`
+/// formatting each field from its name and value
+fn write_entry(
+    item: &ProductCodeMap,
+    fields: &[Field],
+    line: impl Fn(&str, &serde_json::Value) -> String,
+) -> String {
+    let mut out = String::from("{\n");
+    for &field in fields {
         out.push_str(&format!(
-            "\t\t[\"{}\"] = {{\n\t\t\tName = \"{}\",\n\t\t\tId = {},\n\t\t\tImage = \"{}\"\n\t\t}},\n",
-            product.name, product.name, product.id, product.image
+            "\t\t\t{}\n",
+            line(field_name(field), &field_value(item, field))
         ));
     }
+    out.push_str("\t\t}");
+    out
+}
+
+fn generate_luau(
+    products: &[ProductCodeMap],
+    passes: &[ProductCodeMap],
+    fields: &[Field],
+) -> String {
+    let mut out = String::new();
 
-    out.push_str("\t},\n");
-    out.push_str("\tGamepasses = {\n");
+    out.push_str("--!strict\n");
+    out.push_str("-- Autogenerated by jaxon CLI\n\n");
 
-    for pass in passes.values() {
+    out.push_str("export type ProductInfo = {\n");
+    for &field in fields {
         out.push_str(&format!(
-            "\t\t[\"{}\"] = {{\n\t\t\tName = \"{}\",\n\t\t\tId = {},\n\t\t\tImage = \"{}\"\n\t\t}},\n",
-            pass.name, pass.name, pass.id, pass.image
+            "\t{}: {},\n",
+            field_name(field),
+            luau_field_type(field)
         ));
     }
+    out.push_str("}\n\n");
+    out.push_str(&format!(
+        "export type ProductName = {}\n",
+        luau_union(products)
+    ));
+    out.push_str(&format!(
+        "export type GamepassName = {}\n\n",
+        luau_union(passes)
+    ));
+
+    out.push_str("export type Catalog = {\n");
+    out.push_str("\tProducts: { [ProductName]: ProductInfo },\n");
+    out.push_str("\tGamepasses: { [GamepassName]: ProductInfo },\n");
+    out.push_str("\tProductsById: { [number]: ProductInfo },\n");
+    out.push_str("\tGamepassesById: { [number]: ProductInfo },\n");
+    out.push_str("}\n\n");
+
+    let write_group = |out: &mut String, name: &str, items: &[ProductCodeMap]| {
+        out.push_str(&format!("\t{} = {{\n", name));
+        for item in items {
+            let entry = write_entry(item, fields, |name, value| {
+                format!("{} = {},", name, luau_value(value))
+            });
+            out.push_str(&format!("\t\t[{}] = {},\n", luau_string(&item.key), entry));
+        }
+        out.push_str("\t},\n");
+    };
 
-    out.push_str("\t},\n");
+    out.push_str("local Products: Catalog = {\n");
+    write_group(&mut out, "Products", products);
+    write_group(&mut out, "Gamepasses", passes);
     out.push_str("\tProductsById = {},\n");
     out.push_str("\tGamepassesById = {},\n");
     out.push_str("}\n");
-    out.push_str("\nfor _,product in Products.Products do\n\t\tProducts.ProductsById[product.Id] = product\nend\n");
-    out.push_str("\nfor _,gamepass in Products.Gamepasses do\n\t\tProducts.GamepassesById[gamepass.Id] = gamepass\nend\n");
-    out.push_str("\nreturn Products");
+    out.push_str("\nfor _, product in Products.Products do\n\tProducts.ProductsById[product.Id] = product\nend\n");
+    out.push_str("\nfor _, gamepass in Products.Gamepasses do\n\tProducts.GamepassesById[gamepass.Id] = gamepass\nend\n");
+    out.push_str("\nreturn Products\n");
 
     out
 }
 
 fn generate_typescript(
-    products: &HashMap<i64, ProductCodeMap>,
-    passes: &HashMap<i64, ProductCodeMap>,
+    products: &[ProductCodeMap],
+    passes: &[ProductCodeMap],
+    fields: &[Field],
 ) -> String {
     let mut out = String::new();
 
     out.push_str("// Autogenerated by jaxon CLI\n\n");
 
-    let write_group = |out: &mut String, name: &str, items: &HashMap<i64, ProductCodeMap>| {
+    let ts_entry = |item: &ProductCodeMap| {
+        write_entry(item, fields, |name, value| {
+            format!("{}: {};", name, ts_type(value))
+        })
+    };
+
+    let write_group = |out: &mut String, name: &str, items: &[ProductCodeMap]| {
         out.push_str(&format!("\t{}: {{\n", name));
-        for item in items.values() {
+        for item in items {
             out.push_str(&format!(
-                "\t\t\"{}\": {{\n\t\t\tName: \"{}\";\n\t\t\tId: {};\n\t\t\tImage: \"{}\";\n\t\t}};\n",
-                item.name, item.name, item.id, item.image
+                "\t\t{}: {};\n",
+                ts_string(&item.key),
+                ts_entry(item)
             ));
         }
         out.push_str("\t};\n");
@@ -73,57 +342,343 @@ fn generate_typescript(
     write_group(&mut out, "Products", products);
     write_group(&mut out, "Gamepasses", passes);
 
-    out.push_str("\tProductsById: {\n");
-    for item in products.values() {
-        out.push_str(&format!(
-            "\t\t{}: {{\n\t\t\tName: \"{}\";\n\t\t\tId: {};\n\t\t\tImage: \"{}\";\n\t\t}};\n",
-            item.id, item.name, item.id, item.image
-        ));
-    }
-    out.push_str("\t};\n");
+    let write_by_id = |out: &mut String, name: &str, items: &[ProductCodeMap]| {
+        let mut items: Vec<&ProductCodeMap> = items.iter().collect();
+        items.sort_by_key(|item| item.id);
 
-    out.push_str("\tGamepassesById: {\n");
-    for item in passes.values() {
-        out.push_str(&format!(
-            "\t\t{}: {{\n\t\t\tName: \"{}\";\n\t\t\tId: {};\n\t\t\tImage: \"{}\";\n\t\t}};\n",
-            item.id, item.name, item.id, item.image
-        ));
-    }
-    out.push_str("\t};\n");
+        out.push_str(&format!("\t{}: {{\n", name));
+        for item in items {
+            out.push_str(&format!("\t\t{}: {};\n", item.id, ts_entry(item)));
+        }
+        out.push_str("\t};\n");
+    };
+
+    write_by_id(&mut out, "ProductsById", products);
+    write_by_id(&mut out, "GamepassesById", passes);
 
     out.push_str("};\n\nexport = products;\n");
 
     out
 }
 
+/// Standalone TypeScript module with the catalog as `as const` objects
+fn generate_ts_module(
+    products: &[ProductCodeMap],
+    passes: &[ProductCodeMap],
+    fields: &[Field],
+) -> String {
+    let mut out = String::new();
+
+    out.push_str("// Autogenerated by jaxon CLI\n\n");
+
+    let write_group = |out: &mut String, name: &str, items: &[ProductCodeMap]| {
+        out.push_str(&format!("export const {} = {{\n", name));
+        for item in items {
+            let entry = write_entry(item, fields, |name, value| {
+                format!("{}: {},", name, ts_value(value))
+            });
+            out.push_str(&format!(
+                "\t{}: {},\n",
+                ts_string(&item.key),
+                entry.replace("\n\t", "\n")
+            ));
+        }
+        out.push_str("} as const;\n\n");
+    };
+
+    // The ById tables point at the same objects
+    let write_by_id = |out: &mut String, name: &str, group: &str, items: &[ProductCodeMap]| {
+        let mut items: Vec<&ProductCodeMap> = items.iter().collect();
+        items.sort_by_key(|item| item.id);
+
+        out.push_str(&format!("export const {} = {{\n", name));
+        for item in items {
+            out.push_str(&format!(
+                "\t{}: {}[{}],\n",
+                item.id,
+                group,
+                ts_string(&item.key)
+            ));
+        }
+        out.push_str("} as const;\n\n");
+    };
+
+    write_group(&mut out, "Products", products);
+    write_group(&mut out, "Gamepasses", passes);
+    write_by_id(&mut out, "ProductsById", "Products", products);
+    write_by_id(&mut out, "GamepassesById", "Gamepasses", passes);
+
+    out.push_str("export type ProductName = keyof typeof Products;\n");
+    out.push_str("export type GamepassName = keyof typeof Gamepasses;\n");
+
+    out
+}
+
+/// JSON manifest of the catalog, keyed by entry key like the other outputs
+fn generate_json(
+    products: &[ProductCodeMap],
+    passes: &[ProductCodeMap],
+    fields: &[Field],
+) -> String {
+    let group = |items: &[ProductCodeMap]| -> serde_json::Map<String, serde_json::Value> {
+        items
+            .iter()
+            .map(|item| {
+                let entry = fields
+                    .iter()
+                    .map(|&f| (field_name(f).to_string(), field_value(item, f)))
+                    .collect();
+                (item.key.clone(), serde_json::Value::Object(entry))
+            })
+            .collect()
+    };
+
+    let manifest = serde_json::json!({
+        "Products": group(products),
+        "Gamepasses": group(passes),
+    });
+    let mut out = serde_json::to_string_pretty(&manifest).unwrap_or_default();
+    out.push('\n');
+    out
+}
+
+/// CSV manifest of the catalog with a row per entry, products first.
+/// Lists and objects are written as JSON.
+fn generate_csv(
+    products: &[ProductCodeMap],
+    passes: &[ProductCodeMap],
+    fields: &[Field],
+) -> Result<String> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+
+    let mut header = vec!["Key"];
+    header.extend(fields.iter().map(|&f| field_name(f)));
+    writer.write_record(&header)?;
+
+    for item in products.iter().chain(passes) {
+        let mut row = vec![item.key.clone()];
+        row.extend(fields.iter().map(|&f| match field_value(item, f) {
+            serde_json::Value::Null => String::new(),
+            serde_json::Value::String(s) => s,
+            value => value.to_string(),
+        }));
+        writer.write_record(&row)?;
+    }
+
+    let data = writer.into_inner().context("Failed to write CSV")?;
+    Ok(String::from_utf8(data)?)
+}
+
+/// Renders one output target
+pub fn generate_output(
+    output: &Output,
+    products: &[ProductCodeMap],
+    passes: &[ProductCodeMap],
+) -> Result<String> {
+    let select = |items: &[ProductCodeMap]| -> Vec<ProductCodeMap> {
+        items
+            .iter()
+            .filter(|item| output.includes(item.kind))
+            .cloned()
+            .collect()
+    };
+    let (products, passes) = (select(products), select(passes));
+    let fields = output.fields();
+
+    Ok(match output.format {
+        OutputFormat::Luau => generate_luau(&products, &passes, &fields),
+        OutputFormat::TsDeclarations => generate_typescript(&products, &passes, &fields),
+        OutputFormat::TsModule => generate_ts_module(&products, &passes, &fields),
+        OutputFormat::Json => generate_json(&products, &passes, &fields),
+        OutputFormat::Csv => generate_csv(&products, &passes, &fields)?,
+    })
+}
+
+/// Writes every output target and template. Returns the paths of the generated files.
 pub fn generate_code(
-    products: &HashMap<i64, ProductCodeMap>,
-    passes: &HashMap<i64, ProductCodeMap>,
-    generation: &Generation,
-    files: &Files,
-) -> Result<()> {
-    let output_path = Path::new(&files.output);
-
-    // Create parent directories if they don't exist
-    if let Some(parent) = output_path.parent() {
-        if !parent.as_os_str().is_empty() {
+    products: &[ProductCodeMap],
+    passes: &[ProductCodeMap],
+    config: &Config,
+) -> Result<Vec<String>> {
+    let mut written = Vec::new();
+
+    for output in config.outputs() {
+        let output_path = Path::new(&output.path);
+
+        // Create parent directories if they don't exist
+        if let Some(parent) = output_path.parent()
+            && !parent.as_os_str().is_empty()
+        {
             std::fs::create_dir_all(parent).with_context(|| {
                 format!("Failed to create directories for {}", output_path.display())
             })?;
         }
+
+        let code = generate_output(&output, products, passes)?;
+        std::fs::write(output_path, code)
+            .with_context(|| format!("Failed to write {}", output_path.display()))?;
+        written.push(output.path);
+    }
+
+    template::render_all(&config.templates, products, passes, config)?;
+    written.extend(config.templates.iter().map(|t| t.output.clone()));
+
+    Ok(written)
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn entry(key: Option<&str>, name: &str, product_type: &str, id: i64) -> ProductJson {
+        ProductJson {
+            key: key.map(String::from),
+            id,
+            ..ProductJson::new(name, product_type)
+        }
+    }
+
+    /// Unsorted catalog with names that need escaping in both languages
+    pub(crate) fn catalog() -> (Vec<ProductJson>, HashMap<i64, LockEntry>) {
+        let products = vec![
+            entry(None, "Zeta Pack", "Product", 30),
+            entry(Some("quote"), "The \"Best\" Deal", "Product", 12),
+            entry(None, "Back\\slash", "Product", 7),
+            entry(Some("multiline"), "Line\nbreak\ttab", "Product", 21),
+            ProductJson {
+                description: "Rich \"custard\"\nwith caramel".into(),
+                regional_pricing: true,
+                tags: vec!["food".into(), "limited".into()],
+                metadata: BTreeMap::from([
+                    ("category".into(), serde_json::json!("food")),
+                    (
+                        "tags".into(),
+                        serde_json::json!(["sweet", 2, null, { "a\"b": true }]),
+                    ),
+                ]),
+                ..entry(None, "Crème brûlée", "Product", 5)
+            },
+            entry(None, "Not synced", "Product", 0),
+            ProductJson {
+                for_sale: false,
+                price: Some(250),
+                ..entry(None, "VIP", "Gamepass", 400)
+            },
+            entry(Some("double"), "2x \"Coins\"", "Gamepass", 100),
+            entry(None, "Unknown", "Bundle", 50),
+        ];
+
+        let mut locked = HashMap::new();
+        locked.insert(
+            12,
+            LockEntry::new(
+                ProductKind::Product,
+                "quote",
+                "hash".into(),
+                None,
+                Some(987),
+            ),
+        );
+        (products, locked)
+    }
+
+    /// Compares `actual` with the golden file, or rewrites it when UPDATE_GOLDEN is set
+    pub(crate) fn assert_golden(file: &str, actual: &str) {
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/golden")
+            .join(file);
+
+        if std::env::var_os("UPDATE_GOLDEN").is_some() {
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(&path, actual).unwrap();
+            return;
+        }
+
+        let expected = std::fs::read_to_string(&path)
+            .unwrap_or_else(|_| panic!("Missing {}, run with UPDATE_GOLDEN=1", path.display()));
+        assert_eq!(
+            expected,
+            actual,
+            "{} is out of date, run with UPDATE_GOLDEN=1 to update it",
+            path.display()
+        );
+    }
+
+    #[test]
+    fn luau_matches_golden_file() {
+        let (products, locked) = catalog();
+        let (products_map, passes_map) = build_code_maps(&products, &locked);
+        assert_golden(
+            "Products.luau",
+            &generate_luau(&products_map, &passes_map, &Field::ALL),
+        );
     }
 
-    let luau_code = generate_luau(products, passes);
-    std::fs::write(output_path, luau_code)
-        .with_context(|| format!("Failed to write {}", output_path.display()))?;
+    #[test]
+    fn typescript_matches_golden_file() {
+        let (products, locked) = catalog();
+        let (products_map, passes_map) = build_code_maps(&products, &locked);
+        assert_golden(
+            "Products.d.ts",
+            &generate_typescript(&products_map, &passes_map, &Field::ALL),
+        );
+    }
 
-    if generation.typescript {
-        // Derive the .d.ts path by swapping the extension
-        let ts_path = output_path.with_extension("d.ts");
-        let ts_code = generate_typescript(products, passes);
-        std::fs::write(&ts_path, ts_code)
-            .with_context(|| format!("Failed to write {}", ts_path.display()))?;
+    #[test]
+    fn ts_module_matches_golden_file() {
+        let (products, locked) = catalog();
+        let (products_map, passes_map) = build_code_maps(&products, &locked);
+        assert_golden(
+            "Products.ts",
+            &generate_ts_module(&products_map, &passes_map, &Field::ALL),
+        );
     }
 
-    Ok(())
+    #[test]
+    fn filtered_json_matches_golden_file() {
+        let (products, locked) = catalog();
+        let (products_map, passes_map) = build_code_maps(&products, &locked);
+        let output = Output {
+            format: OutputFormat::Json,
+            path: "products.json".into(),
+            kinds: vec![ProductKind::Gamepass],
+            fields: vec![Field::Name, Field::Price],
+        };
+        assert_golden(
+            "Gamepasses.json",
+            &generate_output(&output, &products_map, &passes_map).unwrap(),
+        );
+    }
+
+    #[test]
+    fn csv_matches_golden_file() {
+        let (products, locked) = catalog();
+        let (products_map, passes_map) = build_code_maps(&products, &locked);
+        assert_golden(
+            "Products.csv",
+            &generate_csv(&products_map, &passes_map, &Field::ALL).unwrap(),
+        );
+    }
+
+    #[test]
+    fn output_does_not_depend_on_catalog_order() {
+        let (mut products, locked) = catalog();
+        let (products_map, passes_map) = build_code_maps(&products, &locked);
+        let first = generate_luau(&products_map, &passes_map, &Field::ALL);
+
+        products.reverse();
+        let (products_map, passes_map) = build_code_maps(&products, &locked);
+        assert_eq!(
+            first,
+            generate_luau(&products_map, &passes_map, &Field::ALL)
+        );
+    }
+
+    #[test]
+    fn escapes_strings() {
+        assert_eq!(luau_string("a\"b\\c\nd"), r#""a\"b\\c\nd""#);
+        assert_eq!(luau_string("bell\u{7}"), r#""bell\u{7}""#);
+        assert_eq!(ts_string("a\"b\\c\nd"), r#""a\"b\\c\nd""#);
+    }
 }
@@ -0,0 +1,23 @@
+use anyhow::Result;
+use std::path::Path;
+
+use crate::{
+    config::{self, Config},
+    products, validate,
+};
+
+pub fn run(config_file: &Path) -> Result<()> {
+    // The catalog location is the only setting needed, so a project without a
+    // universe ID yet can still be checked. A config named with --project must exist.
+    let config = if config_file.exists() || config_file != Path::new(config::TOML_FILE) {
+        config::read_config(config_file)?
+    } else {
+        Config::default()
+    };
+    let products_list = products::read_products(config.catalog())?;
+
+    validate::check(&products_list)?;
+
+    println!("The catalog is valid ({} entries)", products_list.len());
+    Ok(())
+}
@@ -0,0 +1,58 @@
+use anyhow::{Context, Result};
+use std::path::Path;
+
+use crate::{
+    cli::{ExportArgs, ExportFormat},
+    code::{build_code_maps, generate_output},
+    config::{self, Output, OutputFormat},
+    lock, plan, products,
+};
+
+pub fn run(args: &ExportArgs, config_file: &Path, env: Option<&str>) -> Result<()> {
+    let config = config::load_config(config_file, env)?;
+
+    let mut products_list = products::read_products(config.catalog())?;
+    products::check_keys(&products_list)?;
+    let locked = lock::get_entries(&config.lock_file(), &products_list)?;
+    plan::resolve_ids(&config, &mut products_list, &locked);
+
+    let (products_map, passes_map) = build_code_maps(&products_list, &locked);
+    let output = Output {
+        format: match args.format {
+            ExportFormat::Json => OutputFormat::Json,
+            ExportFormat::Csv => OutputFormat::Csv,
+        },
+        path: String::new(),
+        kinds: Vec::new(),
+        fields: Vec::new(),
+    };
+    let manifest = generate_output(&output, &products_map, &passes_map)?;
+
+    let unsynced = products_list.iter().filter(|p| p.id <= 0).count();
+
+    match &args.output {
+        Some(path) => {
+            std::fs::write(path, manifest)
+                .with_context(|| format!("Failed to write {}", path.display()))?;
+            if unsynced > 0 {
+                println!(
+                    "{} entries have not been synced yet and were left out",
+                    unsynced
+                );
+            }
+            println!("Exported {}", path.display());
+        }
+        None => {
+            // Keep stdout clean for pipes
+            if unsynced > 0 {
+                eprintln!(
+                    "{} entries have not been synced yet and were left out",
+                    unsynced
+                );
+            }
+            print!("{}", manifest);
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,35 @@
+use anyhow::Result;
+use std::path::Path;
+
+use crate::{
+    code::{build_code_maps, generate_code},
+    config, lock, plan, products,
+};
+
+pub fn run(config_file: &Path, env: Option<&str>) -> Result<()> {
+    let config = config::load_config(config_file, env)?;
+
+    let mut products_list = products::read_products(config.catalog())?;
+    products::check_keys(&products_list)?;
+    let locked = lock::get_entries(&config.lock_file(), &products_list)?;
+    plan::resolve_ids(&config, &mut products_list, &locked);
+
+    let unsynced = products_list.iter().filter(|p| p.id <= 0).count();
+    if unsynced > 0 {
+        println!(
+            "{} entries have not been synced yet and were left out",
+            unsynced
+        );
+    }
+
+    let (products_map, passes_map) = build_code_maps(&products_list, &locked);
+    let written = generate_code(&products_map, &passes_map, &config)?;
+
+    if written.is_empty() {
+        println!("Nothing to generate, add [[outputs]] to jaxon.toml");
+    }
+    for path in written {
+        println!("Generated {}", path);
+    }
+    Ok(())
+}
@@ -1,10 +1,14 @@
-use crate::{config, products};
+use crate::{config, products, schema};
 use anyhow::Result;
+use std::path::Path;
 
 pub fn run(minimal: bool) -> Result<()> {
     config::init_toml()?;
+    // jaxon.toml points at its schema, products.schema.json only goes with products.json
+    schema::write_config_schema(Path::new("."))?;
     if !minimal {
         products::init_product_json()?;
+        schema::write_product_schema(Path::new("."))?;
     }
 
     Ok(())
@@ -0,0 +1,16 @@
+use anyhow::Result;
+
+use crate::{
+    cli::MockServerArgs,
+    mock::{Faults, MockServer},
+};
+
+pub fn run(args: &MockServerArgs) -> Result<()> {
+    let faults = Faults {
+        rate_limit_every: args.rate_limit_every,
+        fail_every: args.fail_every,
+    };
+
+    let server = MockServer::new(args.state.clone(), faults)?;
+    server.serve(args.port)
+}
@@ -1,2 +1,9 @@
+pub mod check;
+pub mod export;
+pub mod generate;
 pub mod init;
+pub mod mock_server;
+pub mod pull;
+pub mod schema;
+pub mod status;
 pub mod sync;
@@ -0,0 +1,152 @@
+use anyhow::Result;
+use std::path::Path;
+
+use crate::{
+    backend::{Backend, HttpBackend},
+    config,
+    lock::{self, LockEntry},
+    plan,
+    products::{self, ProductJson, ProductKind},
+    roblox::RemoteEntry,
+};
+
+/// Catalog entry for something that only exists on Roblox so far
+fn new_entry(entry: &RemoteEntry) -> ProductJson {
+    ProductJson {
+        key: None,
+        name: entry.name.clone(),
+        description: entry.description.clone(),
+        product_type: entry.kind.as_str().into(),
+        image: String::new(),
+        price: entry.price,
+        regional_pricing: entry.regional_pricing,
+        id: entry.id,
+        for_sale: entry.for_sale,
+        tags: Vec::new(),
+        metadata: Default::default(),
+        source: None,
+        shared_id: None,
+    }
+}
+
+pub fn run(config_file: &Path, env: Option<&str>) -> Result<()> {
+    let config = config::load_config(config_file, env)?;
+    let api_key = config::load_env(&config)?;
+    let api = config::connect(&config, api_key, None)?;
+    let backend = HttpBackend::new(config.project.universe_id, api);
+
+    let lock_file = config.lock_file();
+    let mut products_list = if !products::catalog_files(config.catalog())?.is_empty() {
+        products::read_products(config.catalog())?
+    } else {
+        Vec::new()
+    };
+    let mut locked = lock::get_entries(&lock_file, &products_list)?;
+    plan::resolve_ids(&config, &mut products_list, &locked);
+
+    println!("Pulling products...");
+
+    let mut remote: Vec<RemoteEntry> = backend.list(ProductKind::Product)?;
+    remote.extend(backend.list(ProductKind::Gamepass)?);
+
+    let mut added = 0;
+    let mut updated = 0;
+
+    for entry in &remote {
+        let kind = entry.kind.as_str();
+
+        // Match on the tracked ID first, then on the key the lock recorded for
+        // that ID, then fall back to an untracked entry with the same name
+        let locked_key = locked.get(&entry.id).and_then(|e| e.key.clone());
+        let existing = products_list
+            .iter()
+            .position(|p| p.id == entry.id && p.product_type == kind)
+            .or_else(|| {
+                products_list.iter().position(|p| {
+                    p.id <= 0 && Some(p.key()) == locked_key.as_deref() && p.product_type == kind
+                })
+            })
+            .or_else(|| {
+                products_list
+                    .iter()
+                    .position(|p| p.id <= 0 && p.name == entry.name && p.product_type == kind)
+            });
+
+        let idx = match existing {
+            Some(idx) => {
+                let product = &mut products_list[idx];
+                product.id = entry.id;
+                product.name = entry.name.clone();
+                product.description = entry.description.clone();
+                product.price = entry.price;
+                product.regional_pricing = entry.regional_pricing;
+                product.for_sale = entry.for_sale;
+                updated += 1;
+                idx
+            }
+            None => {
+                products_list.push(new_entry(entry));
+                added += 1;
+                products_list.len() - 1
+            }
+        };
+
+        let product = &products_list[idx];
+        let hash = lock::get_product_hash(product)?;
+        let image_hash = lock::get_image_hash(&product.image)?;
+        locked.insert(
+            entry.id,
+            LockEntry {
+                shared_id: product.shared_id,
+                ..LockEntry::new(
+                    entry.kind,
+                    product.key(),
+                    hash,
+                    image_hash,
+                    entry.icon_asset_id,
+                )
+            },
+        );
+    }
+
+    // An environment's IDs stay in its lock, products.json keeps its own
+    let mut written = products_list.clone();
+    if config.environment.is_some() {
+        for product in written.iter_mut() {
+            product.id = product.shared_id.unwrap_or(0);
+        }
+    }
+    products::write_products(config.catalog(), &written)?;
+    lock::write_entries_to_lockfile(&lock_file, &locked)?;
+
+    println!(
+        "Pulled {} entries ({} new, {} merged)",
+        remote.len(),
+        added,
+        updated
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::roblox::GamepassResponse;
+    use crate::validate;
+
+    #[test]
+    fn off_sale_entry_without_price_is_valid() {
+        // Roblox leaves out the price of passes that were never put on sale
+        let response: GamepassResponse = serde_json::from_value(serde_json::json!({
+            "gamePassId": 7,
+            "name": "VIP",
+            "description": "",
+            "isForSale": false,
+        }))
+        .unwrap();
+
+        let product = new_entry(&RemoteEntry::from(response));
+        assert_eq!(product.price, None);
+        assert!(validate::validate(&[product]).is_empty());
+    }
+}
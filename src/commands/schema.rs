@@ -0,0 +1,7 @@
+use anyhow::Result;
+
+use crate::{cli::SchemaArgs, schema};
+
+pub fn run(args: &SchemaArgs) -> Result<()> {
+    schema::write_schemas(&args.output)
+}
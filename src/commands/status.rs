@@ -0,0 +1,77 @@
+use anyhow::Result;
+use std::path::Path;
+
+use crate::{
+    backend::{Backend, HttpBackend},
+    config, lock, plan, products, roblox,
+};
+
+pub fn run(config_file: &Path, env: Option<&str>) -> Result<()> {
+    let config = config::load_config(config_file, env)?;
+    let api_key = config::load_env(&config)?;
+    let api = config::connect(&config, api_key, None)?;
+    let backend = HttpBackend::new(config.project.universe_id, api);
+
+    let mut products_list = products::read_products(config.catalog())?;
+    let locked = lock::get_entries(&config.lock_file(), &products_list)?;
+    plan::resolve_ids(&config, &mut products_list, &locked);
+
+    println!("Checking live state...");
+
+    let mut checked = 0;
+    let mut drifted = 0;
+
+    for product in &products_list {
+        let Some(kind) = product.kind() else {
+            continue;
+        };
+        if product.id <= 0 || !locked.contains_key(&product.id) {
+            continue;
+        }
+
+        let label = format!(
+            "{} {} {:?} (#{})",
+            product.location(),
+            product.product_type,
+            product.name,
+            product.id
+        );
+        checked += 1;
+
+        // Entries deleted outside of jaxon are drift too, keep checking the rest
+        let remote = match backend.get(kind, product.id) {
+            Ok(remote) => remote,
+            Err(e) if roblox::is_not_found(&e) => {
+                drifted += 1;
+                println!("  - {}: missing on Roblox", label);
+                continue;
+            }
+            Err(e) => return Err(e),
+        };
+        let changes = plan::diff(product, &remote);
+
+        if changes.is_empty() {
+            continue;
+        }
+
+        drifted += 1;
+        println!("  ~ {}", label);
+        for change in changes {
+            println!(
+                "      {}: {} (live) != {} (products.json)",
+                change.field, change.from, change.to
+            );
+        }
+    }
+
+    if drifted > 0 {
+        anyhow::bail!(
+            "{} of {} tracked entries differ from the live state",
+            drifted,
+            checked
+        );
+    }
+
+    println!("All {} tracked entries match the live state", checked);
+    Ok(())
+}
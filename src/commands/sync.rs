@@ -1,252 +1,257 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
 use std::{
     collections::HashMap,
-    sync::{Arc, Mutex},
-    thread,
+    io::Write,
+    path::Path,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
 };
 
 use crate::{
-    code::{ProductCodeMap, generate_code},
-    config, lock, products,
-    roblox::{self, Product},
+    backend::{Backend, HttpBackend},
+    cli::SyncArgs,
+    code::{build_code_maps, generate_code},
+    config, engine,
+    journal::{self, Journal},
+    lock::{self, LockEntry},
+    plan,
+    products::{self, ProductJson, ProductKind},
+    validate,
 };
 
-const MAX_WORKERS: usize = 1;
+fn confirm(prompt: &str) -> Result<bool> {
+    print!("{} [y/N] ", prompt);
+    std::io::stdout().flush().ok();
 
-pub fn run() -> Result<()> {
-    let config = config::load_config()?;
-    let api_key = config::load_env()?;
+    let mut answer = String::new();
+    std::io::stdin()
+        .read_line(&mut answer)
+        .context("Failed to read confirmation")?;
 
-    let products_list = products::read_products()?;
-    let hashes = lock::get_hashes()?;
+    Ok(matches!(answer.trim(), "y" | "Y" | "yes"))
+}
 
-    let hashes = Arc::new(Mutex::new(hashes));
-    let products_map: Arc<Mutex<HashMap<i64, ProductCodeMap>>> =
-        Arc::new(Mutex::new(HashMap::new()));
-    let passes_map: Arc<Mutex<HashMap<i64, ProductCodeMap>>> = Arc::new(Mutex::new(HashMap::new()));
-    let products_list = Arc::new(Mutex::new(products_list));
+/// Takes entries that were removed from products.json off sale and stops tracking them
+fn prune(
+    backend: &dyn Backend,
+    products_list: &[ProductJson],
+    locked: &mut HashMap<i64, LockEntry>,
+    lock_file: &str,
+    yes: bool,
+) -> Result<()> {
+    let removed = plan::find_removed(products_list, locked);
+    if removed.is_empty() {
+        println!("Nothing to prune");
+        return Ok(());
+    }
 
-    let universe_id = config.project.universe_id;
-    let api_key = Arc::new(api_key);
+    println!("Entries removed from products.json:");
+    plan::print_removed(&removed);
 
-    // Channel for errors from worker threads
-    let (err_tx, err_rx) = std::sync::mpsc::channel::<anyhow::Error>();
-    let (tx, rx) = std::sync::mpsc::channel::<usize>();
-    let rx = Arc::new(Mutex::new(rx));
+    // Entries without a recorded type are listed above, but can't be retired
+    let retirable: Vec<(i64, ProductKind)> = removed
+        .iter()
+        .filter_map(|(id, entry)| entry.kind.map(|kind| (*id, kind)))
+        .collect();
+    if retirable.is_empty() {
+        println!("Nothing to retire");
+        return Ok(());
+    }
 
-    println!("Syncing products...");
+    if !yes && !confirm(&format!("Take {} entries off sale?", retirable.len()))? {
+        println!("Prune cancelled");
+        return Ok(());
+    }
 
-    // Enqueue jobs first
-    let job_count = products_list.lock().unwrap().len();
-    for i in 0..job_count {
-        tx.send(i).ok();
+    for (id, kind) in retirable {
+        backend.set_for_sale(kind, id, false)?;
+        locked.remove(&id);
+        lock::write_entries_to_lockfile(lock_file, locked)?;
+        println!("Retired {} #{}", kind.as_str(), id);
     }
-    drop(tx);
-    let mut handles = vec![];
-    for _ in 0..MAX_WORKERS {
-        let rx = Arc::clone(&rx);
-        let hashes = Arc::clone(&hashes);
-        let products_map = Arc::clone(&products_map);
-        let passes_map = Arc::clone(&passes_map);
-        let products_list = Arc::clone(&products_list);
-        let api_key = Arc::clone(&api_key);
-        let err_tx = err_tx.clone();
-
-        let handle = thread::spawn(move || {
-            loop {
-                let idx = {
-                    let rx = rx.lock().unwrap();
-                    match rx.recv() {
-                        Ok(idx) => idx,
-                        Err(_) => break,
-                    }
-                };
-
-                let product = {
-                    let list = products_list.lock().unwrap();
-                    list[idx].clone()
-                };
-
-                let product_hash = match lock::get_product_hash(&product) {
-                    Ok(h) => h,
-                    Err(e) => {
-                        err_tx.send(e).ok();
-                        break;
-                    }
-                };
-
-                let product_struct = Product {
-                    name: product.name.clone(),
-                    description: product.description.clone(),
-                    image_file: product.image.clone(),
-                    price: product.price,
-                    regional_pricing: product.regional_pricing,
-                };
-
-                if product.id > 0 {
-                    // Check if hash changed — skip if unchanged
-                    let existing_hash = {
-                        let h = hashes.lock().unwrap();
-                        h.get(&product.id).cloned()
-                    };
-                    if let Some(existing) = existing_hash {
-                        if existing == product_hash {
-                            continue;
-                        }
-                    }
-
-                    match product.product_type.as_str() {
-                        "Product" => {
-                            match roblox::update_product(
-                                universe_id,
-                                product.id,
-                                &api_key,
-                                &product_struct,
-                            ) {
-                                Ok(info) => {
-                                    let mut h = hashes.lock().unwrap();
-                                    h.insert(product.id, product_hash);
-                                    drop(h);
-                                    products_list.lock().unwrap()[idx].id = info.product_id;
-                                    products_map.lock().unwrap().insert(
-                                        info.product_id,
-                                        ProductCodeMap {
-                                            name: info.name.clone(),
-                                            id: info.product_id,
-                                            image: format!(
-                                                "rbxassetid://{}",
-                                                info.icon_image_asset_id.unwrap_or(0)
-                                            ),
-                                        },
-                                    );
-                                }
-                                Err(e) => {
-                                    err_tx.send(e).ok();
-                                    break;
-                                }
-                            }
-                        }
-                        "Gamepass" => {
-                            match roblox::update_gamepass(
-                                universe_id,
-                                product.id,
-                                &api_key,
-                                &product_struct,
-                            ) {
-                                Ok(info) => {
-                                    let mut h = hashes.lock().unwrap();
-                                    h.insert(product.id, product_hash);
-                                    drop(h);
-                                    products_list.lock().unwrap()[idx].id = info.game_pass_id;
-                                    passes_map.lock().unwrap().insert(
-                                        info.game_pass_id,
-                                        ProductCodeMap {
-                                            name: info.name.clone(),
-                                            id: info.game_pass_id,
-                                            image: format!(
-                                                "rbxassetid://{}",
-                                                info.icon_asset_id.unwrap_or(0)
-                                            ),
-                                        },
-                                    );
-                                }
-                                Err(e) => {
-                                    err_tx.send(e).ok();
-                                    break;
-                                }
-                            }
-                        }
-                        other => println!("Unknown product type: {}", other),
-                    }
-                } else {
-                    match product.product_type.as_str() {
-                        "Product" => {
-                            match roblox::create_product(universe_id, &api_key, &product_struct) {
-                                Ok(info) => {
-                                    let mut h = hashes.lock().unwrap();
-                                    h.insert(product.id, product_hash);
-                                    drop(h);
-                                    products_list.lock().unwrap()[idx].id = info.product_id;
-                                    products_map.lock().unwrap().insert(
-                                        info.product_id,
-                                        ProductCodeMap {
-                                            name: info.name.clone(),
-                                            id: info.product_id,
-                                            image: format!(
-                                                "rbxassetid://{}",
-                                                info.icon_image_asset_id.unwrap_or(0)
-                                            ),
-                                        },
-                                    );
-                                }
-                                Err(e) => {
-                                    err_tx.send(e).ok();
-                                    break;
-                                }
-                            }
-                        }
-                        "Gamepass" => {
-                            match roblox::create_gamepass(universe_id, &api_key, &product_struct) {
-                                Ok(info) => {
-                                    let mut h = hashes.lock().unwrap();
-                                    h.insert(product.id, product_hash);
-                                    drop(h);
-                                    products_list.lock().unwrap()[idx].id = info.game_pass_id;
-                                    passes_map.lock().unwrap().insert(
-                                        info.game_pass_id,
-                                        ProductCodeMap {
-                                            name: info.name.clone(),
-                                            id: info.game_pass_id,
-                                            image: format!(
-                                                "rbxassetid://{}",
-                                                info.icon_asset_id.unwrap_or(0)
-                                            ),
-                                        },
-                                    );
-                                }
-                                Err(e) => {
-                                    err_tx.send(e).ok();
-                                    break;
-                                }
-                            }
-                        }
-                        other => println!("Unknown product type: {}", other),
-                    }
-                }
+
+    Ok(())
+}
+
+/// Sets `stop` on the first Ctrl-C so the sync ends after the requests in
+/// flight, and exits right away on the second. The journal is left behind for
+/// `--resume` then.
+fn handle_interrupts(stop: Arc<AtomicBool>) -> Result<()> {
+    ctrlc::set_handler(move || {
+        if stop.swap(true, Ordering::SeqCst) {
+            std::process::exit(130);
+        }
+        println!("Stopping after the requests in flight, press Ctrl-C again to quit immediately");
+    })
+    .context("Failed to set Ctrl-C handler")
+}
+
+pub fn run(args: &SyncArgs, config_file: &Path, env: Option<&str>) -> Result<()> {
+    let config = config::load_config(config_file, env)?;
+    let api_key = config::load_env(&config)?;
+    // Environments keep their IDs in their own lock, products.json is left alone
+    let write_ids = config.environment.is_none();
+
+    let catalog = config.catalog();
+    let lock_file = config.lock_file();
+    let journal_file = journal::journal_file(&lock_file);
+
+    let mut products_list = products::read_products(catalog)?;
+    let problems = validate::validate(&products_list);
+    let mut locked = lock::get_entries(&lock_file, &products_list)?;
+
+    let matched = plan::resolve_ids(&config, &mut products_list, &locked);
+    if write_ids && matched > 0 {
+        println!("Matched {} entries to tracked IDs by key", matched);
+    }
+
+    if journal_file.exists() {
+        if !args.resume {
+            anyhow::bail!(
+                "A previous sync was interrupted before it could save its results. Run `jaxon sync --resume` to recover them first."
+            );
+        }
+
+        let entries = journal::read_entries(&journal_file)?;
+        journal::replay(&entries, &mut products_list, &mut locked, &lock_file);
+        if !args.dry_run {
+            if write_ids {
+                products::write_products(catalog, &products_list)?;
             }
-        });
+            lock::write_entries_to_lockfile(&lock_file, &locked)?;
+            journal::remove(&journal_file)?;
+        }
+        println!(
+            "Recovered {} entries from the interrupted sync",
+            entries.len()
+        );
+    } else if args.resume {
+        println!("No interrupted sync to resume");
+    }
+
+    let workers = args.workers.unwrap_or(config.sync.workers).max(1);
+    let api = config::connect(&config, api_key, args.rate_limit)?;
+    let backend = HttpBackend::new(config.project.universe_id, api);
 
-        handles.push(handle);
+    let mut sync_plan = plan::build_plan(&products_list, &locked)?;
+    plan::mark_invalid(&mut sync_plan, &problems);
+
+    if args.dry_run {
+        plan::fetch_changes(&mut sync_plan, &products_list, &backend)?;
+        plan::print_plan(&sync_plan, &products_list);
+        if args.prune {
+            plan::print_removed(&plan::find_removed(&products_list, &locked));
+        }
+        return Ok(());
     }
 
-    // Drop the extra err_tx so the channel closes when all threads finish
-    drop(err_tx);
+    validate::report(&problems)?;
+
+    println!("Syncing products...");
+
+    let stop = Arc::new(AtomicBool::new(false));
+    handle_interrupts(stop.clone())?;
+    let journal = Journal::create(&journal_file)?;
+    let result = engine::apply(
+        &backend,
+        sync_plan,
+        products_list,
+        locked,
+        workers,
+        Some(&journal),
+        &stop,
+    );
 
-    // Wait for all workers
-    for handle in handles {
-        handle.join().ok();
+    // Save whatever was pushed, even when the sync failed part of the way
+    if write_ids {
+        products::write_products(catalog, &result.products)?;
     }
 
-    // Check if any thread sent an error
-    if let Ok(e) = err_rx.try_recv() {
+    let mut final_locked = result.locked;
+    lock::write_entries_to_lockfile(&lock_file, &final_locked)?;
+    journal::remove(&journal_file)?;
+
+    if let Some(e) = result.error {
         return Err(e);
     }
 
-    let final_products = products_list.lock().unwrap().clone();
-    products::write_products(&final_products)?;
-
-    let final_hashes = hashes.lock().unwrap().clone();
-    lock::write_hashes_to_lockfile(&final_hashes)?;
+    if args.prune {
+        prune(
+            &backend,
+            &result.products,
+            &mut final_locked,
+            &lock_file,
+            args.yes,
+        )?;
+    }
 
-    let products_map = products_map.lock().unwrap().clone();
-    let passes_map = passes_map.lock().unwrap().clone();
-    generate_code(
-        &products_map,
-        &passes_map,
-        &config.generation,
-        &config.files,
-    )?;
+    // Generate from the whole catalog so unchanged entries are kept in the output
+    let (products_map, passes_map) = build_code_maps(&result.products, &final_locked);
+    generate_code(&products_map, &passes_map, &config)?;
 
     println!("Synced products");
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::MemoryBackend;
+    use crate::roblox::Product;
+
+    #[test]
+    fn prune_retires_only_entries_with_a_type() {
+        let backend = MemoryBackend::default();
+        let product = Product {
+            name: "Old".into(),
+            description: String::new(),
+            image_file: String::new(),
+            price: Some(10),
+            regional_pricing: false,
+            for_sale: true,
+        };
+        let removed = backend.create(ProductKind::Gamepass, &product).unwrap().id;
+        let kept = ProductJson {
+            id: backend.create(ProductKind::Product, &product).unwrap().id,
+            ..ProductJson::new("Kept", "Product")
+        };
+
+        let tracked = |kind| LockEntry {
+            kind,
+            ..LockEntry::new(ProductKind::Product, "key", "hash".into(), None, None)
+        };
+        let mut locked = HashMap::from([
+            (removed, tracked(Some(ProductKind::Gamepass))),
+            (kept.id, tracked(Some(ProductKind::Product))),
+            (999, tracked(None)),
+        ]);
+
+        let lock_file = std::env::temp_dir()
+            .join(format!("jaxon-prune-{}.lock", std::process::id()))
+            .to_string_lossy()
+            .into_owned();
+        prune(
+            &backend,
+            std::slice::from_ref(&kept),
+            &mut locked,
+            &lock_file,
+            true,
+        )
+        .unwrap();
+
+        assert!(
+            !backend
+                .get(ProductKind::Gamepass, removed)
+                .unwrap()
+                .for_sale
+        );
+        assert!(backend.get(ProductKind::Product, kept.id).unwrap().for_sale);
+        // The kind-less entry can't be retired, so it stays tracked
+        let mut ids: Vec<i64> = locked.keys().copied().collect();
+        ids.sort();
+        assert_eq!(ids, [kept.id, 999]);
+        std::fs::remove_file(&lock_file).ok();
+    }
+}
@@ -1,36 +1,376 @@
 use anyhow::{Context, Result};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::env;
+use std::path::Path;
+use std::time::Duration;
+
+use crate::lock;
+use crate::products::{self, ProductKind};
+use crate::roblox::{self, ApiOptions, OpenCloud, retry::RetryPolicy};
 
 pub const TOML_FILE: &str = "jaxon.toml";
 
-#[derive(Deserialize, Serialize, Debug, Default)]
+const API_KEY_ENV: &str = "JAXON_API_KEY";
+
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Default)]
 pub struct Config {
     pub project: Project,
+    #[serde(default)]
     pub generation: Generation,
+    #[serde(default)]
     pub files: Files,
+    #[serde(default)]
+    pub sync: SyncOptions,
+    #[serde(default)]
+    pub network: Network,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub environments: BTreeMap<String, Environment>,
+    /// Generated files. Without any, `files.output` and `generation.typescript` are used.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub outputs: Vec<Output>,
+    /// Extra files rendered from user templates
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub templates: Vec<Template>,
+    /// Name of the environment selected with `--env`
+    #[serde(skip)]
+    pub environment: Option<String>,
 }
 
-#[derive(Deserialize, Serialize, Debug, Default)]
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Default)]
 pub struct Project {
     pub universe_id: i64,
+    /// Base URL of the Open Cloud API, overridden by JAXON_API_URL
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_url: Option<String>,
+}
+
+/// A universe the catalog is published to, such as a test or a live game.
+/// Its IDs are kept in its own lock file instead of products.json.
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Clone)]
+pub struct Environment {
+    pub universe_id: i64,
+    /// Environment variable holding the API key for this universe
+    #[serde(default = "default_api_key_env")]
+    pub api_key_env: String,
+    /// Overrides `files.output`. Without it or `outputs`, every generated file
+    /// gets the environment name inserted, e.g. Products.dev.luau
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output: Option<String>,
+    /// Overrides the project's `outputs` when not empty
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub outputs: Vec<Output>,
+    /// Lock file of this environment. Defaults to the project lock with the
+    /// environment's name inserted, e.g. jaxon.dev.lock.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lock: Option<String>,
+}
+
+fn default_api_key_env() -> String {
+    API_KEY_ENV.into()
+}
+
+/// A minijinja template rendered with the whole catalog on every sync and generate
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Clone)]
+pub struct Template {
+    /// Path of the template file
+    pub template: String,
+    /// Path of the file to write
+    pub output: String,
+}
+
+/// A file generated from the catalog on every sync and generate
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Clone)]
+pub struct Output {
+    pub format: OutputFormat,
+    /// Path of the file to write
+    pub path: String,
+    /// Only these kinds of entries. Defaults to all.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub kinds: Vec<ProductKind>,
+    /// Only these fields of each entry. Defaults to all, `id` is always included.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fields: Vec<Field>,
+}
+
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum OutputFormat {
+    /// Strict Luau module
+    Luau,
+    /// TypeScript declarations for a Luau module, for roblox-ts
+    TsDeclarations,
+    /// Standalone TypeScript module
+    TsModule,
+    /// JSON manifest
+    Json,
+    /// CSV manifest, one row per entry
+    Csv,
+}
+
+/// A field of a generated entry
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Field {
+    Name,
+    Description,
+    Kind,
+    Id,
+    Price,
+    RegionalPricing,
+    ForSale,
+    Image,
+    /// Icon asset ID from the lock, 0 when unknown
+    IconAssetId,
+    Tags,
+    Metadata,
+}
+
+impl Field {
+    /// Every field, in the order they are written
+    pub const ALL: [Field; 11] = [
+        Field::Name,
+        Field::Description,
+        Field::Kind,
+        Field::Id,
+        Field::Price,
+        Field::RegionalPricing,
+        Field::ForSale,
+        Field::Image,
+        Field::IconAssetId,
+        Field::Tags,
+        Field::Metadata,
+    ];
+}
+
+impl Output {
+    /// The selected fields in output order, always with `id` since the
+    /// `ById` tables are keyed by it
+    pub fn fields(&self) -> Vec<Field> {
+        Field::ALL
+            .into_iter()
+            .filter(|f| self.fields.is_empty() || self.fields.contains(f) || *f == Field::Id)
+            .collect()
+    }
+
+    pub fn includes(&self, kind: ProductKind) -> bool {
+        self.kinds.is_empty() || self.kinds.contains(&kind)
+    }
 }
 
-#[derive(Deserialize, Serialize, Debug, Default)]
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Default)]
 pub struct Generation {
     pub typescript: bool,
 }
 
-#[derive(Deserialize, Serialize, Debug, Default)]
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Default)]
 pub struct Files {
+    /// Luau module to generate when there are no `outputs`
+    #[serde(default, skip_serializing_if = "String::is_empty")]
     pub output: String,
+    /// Catalog file, directory or glob such as "products/*.json".
+    /// JSON, TOML and YAML files are supported. Defaults to products.json.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub catalog: Option<String>,
+    /// Lock file. Defaults to jaxon.lock.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lock: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, JsonSchema, Debug)]
+#[serde(default)]
+pub struct SyncOptions {
+    /// Number of entries synced in parallel
+    pub workers: usize,
+    /// Upper bound for requests per second, shared by all workers
+    pub rate_limit: f64,
+}
+
+#[derive(Deserialize, Serialize, JsonSchema, Debug)]
+#[serde(default)]
+pub struct Network {
+    /// Attempts per request before giving up, including the first one
+    pub max_attempts: u32,
+    /// Timeout for a single request, in seconds
+    pub timeout: u64,
+}
+
+impl Default for Network {
+    fn default() -> Self {
+        let policy = RetryPolicy::default();
+        Network {
+            max_attempts: policy.max_attempts,
+            timeout: policy.timeout.as_secs(),
+        }
+    }
+}
+
+impl Network {
+    pub fn retry_policy(&self) -> RetryPolicy {
+        RetryPolicy {
+            max_attempts: self.max_attempts.max(1),
+            timeout: Duration::from_secs(self.timeout),
+        }
+    }
+}
+
+impl Default for SyncOptions {
+    fn default() -> Self {
+        SyncOptions {
+            workers: 4,
+            rate_limit: roblox::api::DEFAULT_RATE,
+        }
+    }
+}
+
+/// Inserts the environment's name before the extensions of a generated file,
+/// e.g. src/Shared/Products.dev.luau
+fn environment_path(path: &str, name: &str) -> String {
+    let path = Path::new(path);
+    let file = path
+        .file_name()
+        .map(|f| f.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let file = match file.split_once('.') {
+        Some((stem, extensions)) => format!("{}.{}.{}", stem, name, extensions),
+        None => format!("{}.{}", file, name),
+    };
+    path.with_file_name(file).to_string_lossy().into_owned()
+}
+
+impl Config {
+    /// The selected environment, if any
+    pub fn current_environment(&self) -> Option<&Environment> {
+        self.environment
+            .as_ref()
+            .and_then(|name| self.environments.get(name))
+    }
+
+    /// Switches the project to an environment: its universe, output and lock file
+    fn select_environment(&mut self, name: &str) -> Result<()> {
+        let Some(environment) = self.environments.get(name).cloned() else {
+            let known: Vec<&str> = self.environments.keys().map(|k| k.as_str()).collect();
+            anyhow::bail!(
+                "Unknown environment {:?}. Environments in jaxon.toml: {}",
+                name,
+                if known.is_empty() {
+                    "none".to_string()
+                } else {
+                    known.join(", ")
+                }
+            );
+        };
+
+        self.project.universe_id = environment.universe_id;
+
+        if !environment.outputs.is_empty() {
+            self.outputs = environment.outputs;
+        } else if let Some(output) = environment.output {
+            if !self.outputs.is_empty() {
+                anyhow::bail!(
+                    "Environment {:?} sets `output`, which only replaces `files.output`. Use [[environments.{}.outputs]] with [[outputs]].",
+                    name,
+                    name
+                );
+            }
+            self.files.output = output;
+        } else {
+            // Never write another universe's IDs over the shared files
+            self.outputs = self
+                .outputs()
+                .into_iter()
+                .map(|output| Output {
+                    path: environment_path(&output.path, name),
+                    ..output
+                })
+                .collect();
+        }
+        for template in &mut self.templates {
+            template.output = environment_path(&template.output, name);
+        }
+
+        self.environment = Some(name.to_string());
+        Ok(())
+    }
+
+    /// Files to generate. Projects without `outputs` get the Luau module at
+    /// `files.output`, and its .d.ts next to it when `generation.typescript` is set.
+    pub fn outputs(&self) -> Vec<Output> {
+        if !self.outputs.is_empty() {
+            return self.outputs.clone();
+        }
+        if self.files.output.is_empty() {
+            return Vec::new();
+        }
+
+        let legacy = |format, path: String| Output {
+            format,
+            path,
+            kinds: Vec::new(),
+            fields: Vec::new(),
+        };
+        let mut outputs = vec![legacy(OutputFormat::Luau, self.files.output.clone())];
+        if self.generation.typescript {
+            let ts_path = Path::new(&self.files.output).with_extension("d.ts");
+            outputs.push(legacy(
+                OutputFormat::TsDeclarations,
+                ts_path.to_string_lossy().into_owned(),
+            ));
+        }
+        outputs
+    }
+
+    /// Catalog file, directory or glob
+    pub fn catalog(&self) -> &str {
+        self.files
+            .catalog
+            .as_deref()
+            .unwrap_or(products::PRODUCT_FILE)
+    }
+
+    /// Lock file of the project, or of the selected environment
+    pub fn lock_file(&self) -> String {
+        let project_lock = self.files.lock.as_deref().unwrap_or(lock::LOCK_FILE);
+
+        match (&self.environment, self.current_environment()) {
+            (
+                _,
+                Some(Environment {
+                    lock: Some(lock), ..
+                }),
+            ) => lock.clone(),
+            (Some(name), _) => Path::new(project_lock)
+                .with_extension(format!("{}.lock", name))
+                .to_string_lossy()
+                .into_owned(),
+            (None, _) => project_lock.to_string(),
+        }
+    }
+}
+
+/// Reads `config_file`, jaxon.toml unless `--project` names another file,
+/// without checking the project settings, for commands that only work on local files
+pub fn read_config(config_file: &Path) -> Result<Config> {
+    let content = std::fs::read_to_string(config_file).with_context(|| {
+        format!(
+            "Failed to load config: {} not found. Did you run `jaxon init`?",
+            config_file.display()
+        )
+    })?;
+
+    let config: Config = toml::from_str(&content)
+        .with_context(|| format!("Failed to parse {}", config_file.display()))?;
+    Ok(config)
 }
 
-pub fn load_config() -> Result<Config> {
-    let content = std::fs::read_to_string(TOML_FILE)
-        .context("Failed to load config: jaxon.toml not found. Did you run `jaxon init`?")?;
+/// Loads `config_file`, switching to the environment `env` when given
+pub fn load_config(config_file: &Path, env: Option<&str>) -> Result<Config> {
+    let mut config = read_config(config_file)?;
 
-    let config: Config = toml::from_str(&content).context("Failed to parse jaxon.toml")?;
+    if let Some(name) = env {
+        config.select_environment(name)?;
+    }
 
     if config.project.universe_id == 0 {
         anyhow::bail!("Config missing required field or invalid: universe_id");
@@ -39,30 +379,134 @@ pub fn load_config() -> Result<Config> {
     Ok(config)
 }
 
-pub fn load_env() -> Result<String> {
+pub fn load_env(config: &Config) -> Result<String> {
+    let name = config
+        .current_environment()
+        .map_or(API_KEY_ENV, |e| e.api_key_env.as_str());
     let api_key =
-        env::var("JAXON_API_KEY").context("JAXON_API_KEY not set in .env or environment")?;
+        env::var(name).with_context(|| format!("{} not set in .env or environment", name))?;
 
     Ok(api_key)
 }
 
+/// Connects to Open Cloud with the API URL and the [sync] and [network]
+/// settings. `rate_limit` overrides the configured rate.
+pub fn connect(config: &Config, api_key: String, rate_limit: Option<f64>) -> Result<OpenCloud> {
+    let base_url = env::var("JAXON_API_URL")
+        .ok()
+        .or_else(|| config.project.api_url.clone())
+        .unwrap_or_else(|| roblox::api::ROBLOX_API_URL.to_string());
+
+    let options = ApiOptions {
+        base_url,
+        rate_limit: rate_limit.unwrap_or(config.sync.rate_limit),
+        retry: config.network.retry_policy(),
+    };
+    OpenCloud::new(options, api_key)
+}
+
 pub fn init_toml() -> Result<()> {
     if std::path::Path::new(TOML_FILE).exists() {
         println!("jaxon.toml already exists");
         return Ok(());
     }
 
-    let content = r#"[project]
+    let content = r#"#:schema ./jaxon.schema.json
+[project]
 universe_id = 0
 
-[generation]
-typescript = false
-
-[files]
-output = "src/Shared/Products.luau"
+[[outputs]]
+format = "luau"
+path = "src/Shared/Products.luau"
 "#;
 
     std::fs::write(TOML_FILE, content).context("Failed to write jaxon.toml")?;
     println!("Created jaxon.toml");
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(content: &str) -> Config {
+        toml::from_str(content).unwrap()
+    }
+
+    #[test]
+    fn environment_path_goes_before_the_extensions() {
+        assert_eq!(
+            environment_path("src/Shared/Products.luau", "dev"),
+            "src/Shared/Products.dev.luau"
+        );
+        assert_eq!(
+            environment_path("out/Products.d.ts", "dev"),
+            "out/Products.dev.d.ts"
+        );
+        assert_eq!(environment_path("Products", "dev"), "Products.dev");
+    }
+
+    #[test]
+    fn environment_renames_outputs_and_templates() {
+        let mut config = config(
+            r#"
+[project]
+universe_id = 1
+
+[environments.dev]
+universe_id = 2
+
+[[outputs]]
+format = "luau"
+path = "src/Products.luau"
+
+[[templates]]
+template = "products.tera"
+output = "docs/products.md"
+"#,
+        );
+        config.select_environment("dev").unwrap();
+
+        assert_eq!(config.project.universe_id, 2);
+        assert_eq!(config.outputs()[0].path, "src/Products.dev.luau");
+        assert_eq!(config.templates[0].output, "docs/products.dev.md");
+    }
+
+    #[test]
+    fn unknown_environment_is_an_error() {
+        let mut config = config("[project]\nuniverse_id = 1\n");
+        let error = config.select_environment("prod").unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .contains("Environments in jaxon.toml: none")
+        );
+    }
+
+    #[test]
+    fn lock_file_per_environment() {
+        let mut config = config(
+            r#"
+[project]
+universe_id = 1
+
+[environments.dev]
+universe_id = 2
+
+[environments.prod]
+universe_id = 3
+lock = "locks/prod.lock"
+"#,
+        );
+        assert_eq!(config.lock_file(), "jaxon.lock");
+
+        config.select_environment("dev").unwrap();
+        assert_eq!(config.lock_file(), "jaxon.dev.lock");
+
+        config.files.lock = Some("state/catalog.lock".into());
+        assert_eq!(config.lock_file(), "state/catalog.dev.lock");
+
+        config.select_environment("prod").unwrap();
+        assert_eq!(config.lock_file(), "locks/prod.lock");
+    }
+}
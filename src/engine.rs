@@ -0,0 +1,304 @@
+use anyhow::Result;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, mpsc};
+use std::thread;
+
+use crate::backend::Backend;
+use crate::journal::{Journal, JournalEntry};
+use crate::lock::LockEntry;
+use crate::plan::{Action, PlannedEntry};
+use crate::products::{ProductJson, ProductKind};
+use crate::roblox::{Product, RemoteEntry};
+use crate::validate;
+
+/// Catalog and lock after a sync. When the sync failed or was stopped part of
+/// the way, they hold every entry that was pushed before that.
+pub struct SyncResult {
+    pub products: Vec<ProductJson>,
+    pub locked: HashMap<i64, LockEntry>,
+    pub error: Option<anyhow::Error>,
+}
+
+fn push_entry(
+    backend: &dyn Backend,
+    kind: ProductKind,
+    product: &ProductJson,
+    upload_icon: bool,
+) -> Result<RemoteEntry> {
+    let data = Product {
+        name: product.name.clone(),
+        description: product.description.clone(),
+        image_file: if upload_icon {
+            product.image.clone()
+        } else {
+            String::new()
+        },
+        // An off-sale entry's placeholder price is left out, keeping the live one
+        price: product
+            .price
+            .filter(|price| validate::is_valid_price(*price)),
+        regional_pricing: product.regional_pricing,
+        for_sale: product.for_sale,
+    };
+
+    if product.id > 0 {
+        backend.update(kind, product.id, &data)
+    } else {
+        backend.create(kind, &data)
+    }
+}
+
+/// Creates and updates every pending entry of `plan` on `backend`, and returns
+/// the catalog with the new IDs filled in along with the updated lock.
+///
+/// Every result is printed and recorded in `journal` as soon as Roblox confirms it. The
+/// first error, or setting `stop`, stops all workers after their current request.
+pub fn apply(
+    backend: &dyn Backend,
+    plan: Vec<PlannedEntry>,
+    products: Vec<ProductJson>,
+    locked: HashMap<i64, LockEntry>,
+    workers: usize,
+    journal: Option<&Journal>,
+    stop: &AtomicBool,
+) -> SyncResult {
+    let failed = AtomicBool::new(false);
+    let locked = Mutex::new(locked);
+    let products = Mutex::new(products);
+
+    // Channel for errors from worker threads
+    let (err_tx, err_rx) = mpsc::channel::<anyhow::Error>();
+    let (tx, rx) = mpsc::channel::<PlannedEntry>();
+    let rx = Mutex::new(rx);
+
+    // Enqueue jobs first
+    for job in plan.into_iter().filter(|entry| entry.is_pending()) {
+        tx.send(job).ok();
+    }
+    drop(tx);
+
+    thread::scope(|scope| {
+        for _ in 0..workers.max(1) {
+            let err_tx = err_tx.clone();
+            let (rx, locked, products, failed) = (&rx, &locked, &products, &failed);
+
+            scope.spawn(move || {
+                loop {
+                    if failed.load(Ordering::SeqCst) || stop.load(Ordering::SeqCst) {
+                        break;
+                    }
+
+                    let job = {
+                        let rx = rx.lock().unwrap();
+                        match rx.recv() {
+                            Ok(job) => job,
+                            Err(_) => break,
+                        }
+                    };
+                    // Only pending entries are queued, and those always have a kind
+                    let Some(kind) = job.kind else {
+                        continue;
+                    };
+
+                    let product = products.lock().unwrap()[job.index].clone();
+
+                    let remote = match push_entry(backend, kind, &product, job.uploads_icon()) {
+                        Ok(remote) => remote,
+                        Err(e) => {
+                            failed.store(true, Ordering::SeqCst);
+                            err_tx
+                                .send(e.context(format!("Failed to sync {:?}", product.name)))
+                                .ok();
+                            break;
+                        }
+                    };
+
+                    let label = format!(
+                        "{} {} {:?} (#{})",
+                        product.location(),
+                        product.product_type,
+                        product.name,
+                        remote.id
+                    );
+                    match job.action {
+                        Action::Create => println!("  + created  {}", label),
+                        _ => println!("  ~ updated  {} [{}]", label, job.updated_parts()),
+                    }
+
+                    if let Some(journal) = journal {
+                        let entry = JournalEntry {
+                            index: job.index,
+                            key: product.key().to_string(),
+                            shared_id: product.shared_id,
+                            kind,
+                            id: remote.id,
+                            hash: job.hash.clone(),
+                            image_hash: job.image_hash.clone(),
+                            icon_asset_id: remote.icon_asset_id,
+                        };
+                        if let Err(e) = journal.record(&entry) {
+                            failed.store(true, Ordering::SeqCst);
+                            err_tx.send(e).ok();
+                        }
+                    }
+
+                    locked.lock().unwrap().insert(
+                        remote.id,
+                        LockEntry {
+                            shared_id: product.shared_id,
+                            ..LockEntry::new(
+                                kind,
+                                product.key(),
+                                job.hash,
+                                job.image_hash,
+                                remote.icon_asset_id,
+                            )
+                        },
+                    );
+                    products.lock().unwrap()[job.index].id = remote.id;
+                }
+            });
+        }
+    });
+
+    // Check if any thread sent an error
+    drop(err_tx);
+    let unfinished = rx.into_inner().unwrap().try_recv().is_ok();
+    let error = match err_rx.try_recv() {
+        Ok(e) => Some(e),
+        Err(_) if unfinished => Some(anyhow::anyhow!("Sync interrupted")),
+        Err(_) => None,
+    };
+
+    SyncResult {
+        products: products.into_inner().unwrap(),
+        locked: locked.into_inner().unwrap(),
+        error,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::MemoryBackend;
+    use crate::plan;
+
+    fn entry(name: &str, price: i64) -> ProductJson {
+        ProductJson {
+            price: Some(price),
+            ..ProductJson::new(name, "Product")
+        }
+    }
+
+    fn sync(
+        backend: &MemoryBackend,
+        products: Vec<ProductJson>,
+        locked: HashMap<i64, LockEntry>,
+    ) -> SyncResult {
+        let plan = plan::build_plan(&products, &locked).unwrap();
+        apply(
+            backend,
+            plan,
+            products,
+            locked,
+            1,
+            None,
+            &AtomicBool::new(false),
+        )
+    }
+
+    #[test]
+    fn create_fills_in_id_and_lock() {
+        let backend = MemoryBackend::default();
+        let result = sync(&backend, vec![entry("Coins", 5)], HashMap::new());
+
+        assert!(result.error.is_none());
+        let id = result.products[0].id;
+        assert!(id > 0);
+        assert_eq!(result.locked[&id].key.as_deref(), Some("Coins"));
+        assert_eq!(
+            backend.get(ProductKind::Product, id).unwrap().price,
+            Some(5)
+        );
+    }
+
+    #[test]
+    fn unchanged_entry_is_skipped() {
+        let backend = MemoryBackend::default();
+        let first = sync(&backend, vec![entry("Coins", 5)], HashMap::new());
+
+        let plan = plan::build_plan(&first.products, &first.locked).unwrap();
+        assert!(matches!(plan[0].action, Action::Skip));
+
+        let second = sync(&backend, first.products.clone(), first.locked);
+        assert!(second.error.is_none());
+        assert_eq!(second.products[0].id, first.products[0].id);
+        assert_eq!(backend.list(ProductKind::Product).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn failure_keeps_entries_pushed_before_it() {
+        let backend = MemoryBackend::default();
+        // The memory backend rejects entries without a name
+        let products = vec![entry("Coins", 5), entry("", 10), entry("Gems", 20)];
+        let result = sync(&backend, products, HashMap::new());
+
+        let error = result.error.expect("the second entry should fail");
+        assert!(format!("{:#}", error).contains("name is required"));
+
+        let id = result.products[0].id;
+        assert!(id > 0);
+        assert!(result.locked.contains_key(&id));
+        assert_eq!(result.products[1].id, 0);
+        assert_eq!(result.products[2].id, 0);
+        assert_eq!(result.locked.len(), 1);
+    }
+
+    #[test]
+    fn renamed_environment_entry_is_updated() {
+        let backend = MemoryBackend::default();
+        let shared = ProductJson {
+            shared_id: Some(42),
+            ..entry("Coins", 5)
+        };
+        let first = sync(&backend, vec![shared.clone()], HashMap::new());
+        let id = first.products[0].id;
+        assert_eq!(first.locked[&id].shared_id, Some(42));
+
+        let mut renamed = vec![ProductJson {
+            name: "Gold Coins".into(),
+            ..shared
+        }];
+        assert_eq!(plan::match_keys(&mut renamed, &first.locked), 1);
+        assert_eq!(renamed[0].id, id);
+
+        let second = sync(&backend, renamed, first.locked);
+        assert!(second.error.is_none());
+        assert_eq!(backend.list(ProductKind::Product).unwrap().len(), 1);
+        assert_eq!(
+            backend.get(ProductKind::Product, id).unwrap().name,
+            "Gold Coins"
+        );
+    }
+
+    #[test]
+    fn stop_leaves_pending_entries() {
+        let backend = MemoryBackend::default();
+        let products = vec![entry("Coins", 5)];
+        let plan = plan::build_plan(&products, &HashMap::new()).unwrap();
+        let result = apply(
+            &backend,
+            plan,
+            products,
+            HashMap::new(),
+            1,
+            None,
+            &AtomicBool::new(true),
+        );
+
+        assert!(result.error.is_some());
+        assert_eq!(result.products[0].id, 0);
+        assert!(backend.list(ProductKind::Product).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,25 @@
+use anyhow::{Context, Result};
+use std::io::Write;
+use std::path::Path;
+
+/// Replaces `path` with `data` in one step, so a crash leaves either the old
+/// or the new contents behind and never a truncated file. The data is written
+/// to a temporary file next to `path` first, then renamed over it.
+pub fn write_atomic(path: &Path, data: &str) -> Result<()> {
+    let name = path
+        .file_name()
+        .with_context(|| format!("Invalid file path {}", path.display()))?;
+    let temp = path.with_file_name(format!(".{}.tmp", name.to_string_lossy()));
+
+    let result = std::fs::File::create(&temp)
+        .and_then(|mut file| {
+            file.write_all(data.as_bytes())?;
+            file.sync_all()
+        })
+        .and_then(|_| std::fs::rename(&temp, path));
+
+    if result.is_err() {
+        std::fs::remove_file(&temp).ok();
+    }
+    result.with_context(|| format!("Failed to write {}", path.display()))
+}
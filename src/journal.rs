@@ -0,0 +1,261 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use crate::lock::LockEntry;
+use crate::products::{ProductJson, ProductKind};
+
+/// The journal sits next to the lock it belongs to, e.g. jaxon.journal for jaxon.lock
+pub fn journal_file(lock_file: &str) -> PathBuf {
+    PathBuf::from(lock_file).with_extension("journal")
+}
+
+/// One entry that was created or updated on Roblox during a sync
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct JournalEntry {
+    /// Position in products.json when the sync started
+    pub index: usize,
+    pub key: String,
+    /// See `LockEntry::shared_id`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shared_id: Option<i64>,
+    pub kind: ProductKind,
+    pub id: i64,
+    pub hash: String,
+    pub image_hash: Option<String>,
+    pub icon_asset_id: Option<i64>,
+}
+
+/// Append-only record of the results of a running sync, one JSON object per
+/// line. Every line is flushed to disk before the next request, so the IDs of
+/// created entries survive a crash and can be recovered with `sync --resume`.
+pub struct Journal {
+    path: PathBuf,
+    file: Mutex<File>,
+}
+
+impl Journal {
+    pub fn create(path: &Path) -> Result<Self> {
+        if let Some(parent) = path.parent()
+            && !parent.as_os_str().is_empty()
+        {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create directories for {}", path.display()))?;
+        }
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .with_context(|| format!("Failed to create {}", path.display()))?;
+
+        Ok(Journal {
+            path: path.to_path_buf(),
+            file: Mutex::new(file),
+        })
+    }
+
+    pub fn record(&self, entry: &JournalEntry) -> Result<()> {
+        let mut line = serde_json::to_string(entry).context("Failed to serialize journal entry")?;
+        line.push('\n');
+
+        let mut file = self.file.lock().unwrap();
+        file.write_all(line.as_bytes())
+            .and_then(|_| file.sync_data())
+            .with_context(|| format!("Failed to write {}", self.path.display()))
+    }
+}
+
+pub fn read_entries(path: &Path) -> Result<Vec<JournalEntry>> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    parse_entries(&content).with_context(|| format!("Failed to parse {}", path.display()))
+}
+
+/// Parses the journal lines. A crash while appending can only cut off the last
+/// line, whose request is then treated as never confirmed. Any other bad line
+/// would lose the ID of a created entry, so it is an error.
+fn parse_entries(content: &str) -> Result<Vec<JournalEntry>> {
+    let lines: Vec<(usize, &str)> = content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .collect();
+
+    let mut entries = Vec::with_capacity(lines.len());
+    for (position, (number, line)) in lines.iter().enumerate() {
+        match serde_json::from_str(line) {
+            Ok(entry) => entries.push(entry),
+            Err(_) if position + 1 == lines.len() && !content.ends_with('\n') => {}
+            Err(e) => {
+                return Err(e).with_context(|| format!("Invalid entry on line {}", number + 1));
+            }
+        }
+    }
+    Ok(entries)
+}
+
+pub fn remove(path: &Path) -> Result<()> {
+    if path.exists() {
+        std::fs::remove_file(path)
+            .with_context(|| format!("Failed to remove {}", path.display()))?;
+    }
+    Ok(())
+}
+
+/// Finds the catalog entry a journal entry was recorded for. Falls back to
+/// key and type when products.json was edited after the sync started.
+fn find_product(products: &[ProductJson], entry: &JournalEntry) -> Option<usize> {
+    let is_match =
+        |p: &ProductJson| p.key() == entry.key && p.kind() == Some(entry.kind) && p.id <= 0;
+
+    match products.get(entry.index) {
+        Some(p) if p.id == entry.id || is_match(p) => Some(entry.index),
+        _ => products
+            .iter()
+            .position(|p| p.id == entry.id)
+            .or_else(|| products.iter().position(is_match)),
+    }
+}
+
+/// Applies the journal entries to the catalog and to the lock at `lock_file`
+pub fn replay(
+    entries: &[JournalEntry],
+    products: &mut [ProductJson],
+    locked: &mut HashMap<i64, LockEntry>,
+    lock_file: &str,
+) {
+    for entry in entries {
+        match find_product(products, entry) {
+            Some(index) => products[index].id = entry.id,
+            None => println!(
+                "{} {:?} (#{}) is no longer in products.json, it stays tracked in {}",
+                entry.kind.as_str(),
+                entry.key,
+                entry.id,
+                lock_file
+            ),
+        }
+
+        locked.insert(
+            entry.id,
+            LockEntry {
+                shared_id: entry.shared_id,
+                ..LockEntry::new(
+                    entry.kind,
+                    &entry.key,
+                    entry.hash.clone(),
+                    entry.image_hash.clone(),
+                    entry.icon_asset_id,
+                )
+            },
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn product(key: Option<&str>, name: &str, id: i64) -> ProductJson {
+        ProductJson {
+            key: key.map(String::from),
+            id,
+            ..ProductJson::new(name, "Product")
+        }
+    }
+
+    fn journal_entry(index: usize, key: &str, id: i64) -> JournalEntry {
+        JournalEntry {
+            index,
+            key: key.into(),
+            shared_id: None,
+            kind: ProductKind::Product,
+            id,
+            hash: "hash".into(),
+            image_hash: None,
+            icon_asset_id: Some(99),
+        }
+    }
+
+    fn line(entry: &JournalEntry) -> String {
+        serde_json::to_string(entry).unwrap()
+    }
+
+    #[test]
+    fn cut_off_last_line_is_ignored() {
+        let content = format!(
+            "{}\n{{\"index\":1,\"ke",
+            line(&journal_entry(0, "Coins", 5))
+        );
+        let entries = parse_entries(&content).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].id, 5);
+    }
+
+    #[test]
+    fn bad_line_before_the_last_is_an_error() {
+        let content = format!(
+            "{}\nnot json\n{}\n",
+            line(&journal_entry(0, "Coins", 5)),
+            line(&journal_entry(1, "Gems", 6))
+        );
+        let error = parse_entries(&content).unwrap_err();
+        assert!(error.to_string().contains("line 2"));
+    }
+
+    #[test]
+    fn replay_fills_in_ids_and_lock() {
+        let mut products = vec![product(None, "Coins", 0), product(None, "Gems", 0)];
+        let mut locked = HashMap::new();
+
+        replay(
+            &[journal_entry(1, "Gems", 6)],
+            &mut products,
+            &mut locked,
+            "jaxon.lock",
+        );
+
+        assert_eq!(products[0].id, 0);
+        assert_eq!(products[1].id, 6);
+        assert_eq!(locked[&6].key.as_deref(), Some("Gems"));
+        assert_eq!(locked[&6].icon_asset_id, Some(99));
+    }
+
+    #[test]
+    fn replay_finds_moved_entries_by_key() {
+        // An entry was inserted at the top after the sync started
+        let mut products = vec![product(None, "New", 0), product(Some("coins"), "Coins", 0)];
+        let mut locked = HashMap::new();
+
+        replay(
+            &[journal_entry(0, "coins", 5)],
+            &mut products,
+            &mut locked,
+            "jaxon.lock",
+        );
+
+        assert_eq!(products[0].id, 0);
+        assert_eq!(products[1].id, 5);
+    }
+
+    #[test]
+    fn replay_keeps_removed_entries_in_the_lock() {
+        let mut products = vec![product(None, "Gems", 6)];
+        let mut locked = HashMap::new();
+
+        replay(
+            &[journal_entry(0, "Coins", 5)],
+            &mut products,
+            &mut locked,
+            "jaxon.lock",
+        );
+
+        assert_eq!(products[0].id, 6);
+        assert!(locked.contains_key(&5));
+    }
+}
@@ -1,34 +1,135 @@
 use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
-use std::collections::HashMap;
-use std::io::{BufRead, Write};
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
 
-use crate::products::ProductJson;
+use crate::files;
+use crate::products::{ProductJson, ProductKind};
 use crate::roblox::Product;
 
 pub const LOCK_FILE: &str = "jaxon.lock";
 
-fn lock_file_exists() -> bool {
-    std::path::Path::new(LOCK_FILE).exists()
+/// Version of the lock file layout
+pub const LOCK_VERSION: u32 = 2;
+
+/// Version of the algorithm in `get_product_hash`. Entries hashed with an
+/// older version are treated as changed.
+///
+/// 1: serialized product including the image path
+/// 2: serialized product without the image, which is hashed by content instead
+pub const HASH_VERSION: u32 = 2;
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct LockFile {
+    version: u32,
+    #[serde(default)]
+    entries: BTreeMap<i64, LockEntry>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LockEntry {
+    /// Unknown for entries migrated from a v1 lock that are no longer in products.json
+    pub kind: Option<ProductKind>,
+    /// Key of the entry on the last sync, used to find its ID again when
+    /// `productId` is missing from products.json
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key: Option<String>,
+    /// `productId` of the entry in products.json, recorded in environment
+    /// locks so renamed entries are still matched
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shared_id: Option<i64>,
+    pub hash: String,
+    pub hash_version: u32,
+    /// SHA-256 of the image file that was uploaded
+    pub image_hash: Option<String>,
+    /// Icon asset ID Roblox reported on the last sync, used for offline codegen
+    pub icon_asset_id: Option<i64>,
+    /// Unix timestamp (seconds) of the last sync
+    pub synced_at: Option<u64>,
+}
+
+impl LockEntry {
+    pub fn new(
+        kind: ProductKind,
+        key: &str,
+        hash: String,
+        image_hash: Option<String>,
+        icon_asset_id: Option<i64>,
+    ) -> Self {
+        let synced_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .ok();
+
+        LockEntry {
+            kind: Some(kind),
+            key: Some(key.to_string()),
+            shared_id: None,
+            hash,
+            hash_version: HASH_VERSION,
+            image_hash,
+            icon_asset_id,
+            synced_at,
+        }
+    }
+
+    /// Whether the entry was recorded with `hash` by the current hash algorithm
+    pub fn matches(&self, hash: &str) -> bool {
+        self.hash_version == HASH_VERSION && self.hash == hash
+    }
 }
 
-fn create_lock_file() -> Result<()> {
-    std::fs::write(LOCK_FILE, "# this file was generated by jaxon\n")
-        .context("Failed to create jaxon.lock")?;
+fn write_lock_file(lock_file: &str, entries: BTreeMap<i64, LockEntry>) -> Result<()> {
+    let lock = LockFile {
+        version: LOCK_VERSION,
+        entries,
+    };
+    let mut data = serde_json::to_string_pretty(&lock)
+        .with_context(|| format!("Failed to serialize {}", lock_file))?;
+    data.push('\n');
+    if let Some(parent) = Path::new(lock_file).parent()
+        && !parent.as_os_str().is_empty()
+    {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create directories for {}", lock_file))?;
+    }
+    files::write_atomic(Path::new(lock_file), &data)?;
     Ok(())
 }
 
-pub fn get_hashes() -> Result<HashMap<i64, String>> {
-    if !lock_file_exists() {
-        create_lock_file()?;
+/// Hash the v1 lock recorded: the product serialized with its image path
+fn get_v1_hash(product: &ProductJson) -> Option<String> {
+    #[derive(Serialize)]
+    struct V1Product<'a> {
+        name: &'a str,
+        description: &'a str,
+        #[serde(rename = "imageFile")]
+        image_file: &'a str,
+        price: i64,
+        #[serde(rename = "isRegionalPricingEnabled")]
+        regional_pricing: bool,
     }
 
-    let file = std::fs::File::open(LOCK_FILE).context("Failed to open jaxon.lock")?;
-    let reader = std::io::BufReader::new(file);
+    let input = V1Product {
+        name: &product.name,
+        description: &product.description,
+        image_file: &product.image,
+        price: product.price?,
+        regional_pricing: product.regional_pricing,
+    };
+    let data = serde_json::to_vec(&input).ok()?;
+    Some(hex::encode(Sha256::digest(&data)))
+}
 
-    let mut hashes = HashMap::new();
-    for line in reader.lines() {
-        let line = line.context("Failed to read jaxon.lock")?;
+/// Parses the v1 format of `id=hash` lines. Entries whose catalog entry still
+/// has the hash that was synced are rehashed, so they aren't pushed again.
+fn parse_v1(content: &str, products: &[ProductJson]) -> Result<BTreeMap<i64, LockEntry>> {
+    let mut entries = BTreeMap::new();
+    for line in content.lines() {
         let line = line.trim();
 
         if line.is_empty() || line.starts_with('#') {
@@ -50,50 +151,205 @@ pub fn get_hashes() -> Result<HashMap<i64, String>> {
             anyhow::bail!("Empty hash for product id {}", id)
         }
 
-        hashes.insert(id, hash);
+        // v1 did not record the kind, so it is taken from the catalog. v1 did
+        // not know `forSale` either, so off-sale entries still have to be pushed.
+        let product = products.iter().find(|p| p.id == id && p.kind().is_some());
+        let entry = match product {
+            Some(product)
+                if product.for_sale && get_v1_hash(product).as_deref() == Some(hash.as_str()) =>
+            {
+                LockEntry {
+                    synced_at: None,
+                    ..LockEntry::new(
+                        product.kind().unwrap(),
+                        product.key(),
+                        get_product_hash(product)?,
+                        get_image_hash(&product.image)?,
+                        None,
+                    )
+                }
+            }
+            _ => LockEntry {
+                kind: product.and_then(|p| p.kind()),
+                key: None,
+                shared_id: None,
+                hash,
+                hash_version: 1,
+                image_hash: None,
+                icon_asset_id: None,
+                synced_at: None,
+            },
+        };
+        entries.insert(id, entry);
     }
 
-    Ok(hashes)
+    Ok(entries)
 }
 
+/// Reads the lock at `lock_file`, or no entries when there is none yet. A v1
+/// lock is migrated in memory, using `products` for what v1 did not record,
+/// and saved in the new format by the next command that writes the lock.
+pub fn get_entries(lock_file: &str, products: &[ProductJson]) -> Result<HashMap<i64, LockEntry>> {
+    if !Path::new(lock_file).exists() {
+        return Ok(HashMap::new());
+    }
+
+    let content = std::fs::read_to_string(lock_file)
+        .with_context(|| format!("Failed to read {}", lock_file))?;
+
+    if !content.trim_start().starts_with('{') {
+        let entries = parse_v1(&content, products)?;
+        println!(
+            "{} uses the version 1 format, it is saved as version {} on the next sync",
+            lock_file, LOCK_VERSION
+        );
+        return Ok(entries.into_iter().collect());
+    }
+
+    let lock: LockFile =
+        serde_json::from_str(&content).with_context(|| format!("Failed to parse {}", lock_file))?;
+    if lock.version > LOCK_VERSION {
+        anyhow::bail!(
+            "{} has version {}, but this jaxon only supports up to {}. Please update jaxon.",
+            lock_file,
+            lock.version,
+            LOCK_VERSION
+        );
+    }
+
+    Ok(lock.entries.into_iter().collect())
+}
+
+/// Hashes the metadata of a product. The image is covered by `get_image_hash`.
 pub fn get_product_hash(product: &ProductJson) -> Result<String> {
     let input = Product {
         name: product.name.clone(),
         description: product.description.clone(),
-        image_file: product.image.clone(),
+        image_file: String::new(),
         price: product.price,
         regional_pricing: product.regional_pricing,
+        for_sale: product.for_sale,
     };
     let data = serde_json::to_vec(&input).context("Failed to serialize product for hashing")?;
     let hash = Sha256::digest(&data);
     Ok(hex::encode(hash))
 }
 
-pub fn write_hashes_to_lockfile(hashes: &HashMap<i64, String>) -> Result<()> {
-    let file = std::fs::File::open(LOCK_FILE).context("Failed to create jaxon.lock")?;
-    let reader = std::io::BufReader::new(file);
-    let lines: Vec<String> = reader
-        .lines()
-        .collect::<std::io::Result<_>>()
-        .context("Failed to read jaxon.lock")?;
+/// Hashes the contents of an image file. Returns `None` when there is no image to hash.
+pub fn get_image_hash(path: &str) -> Result<Option<String>> {
+    if path.is_empty() || !Path::new(path).exists() {
+        return Ok(None);
+    }
+
+    let data = std::fs::read(path).with_context(|| format!("Failed to read image {}", path))?;
+    Ok(Some(hex::encode(Sha256::digest(&data))))
+}
+
+pub fn write_entries_to_lockfile(lock_file: &str, entries: &HashMap<i64, LockEntry>) -> Result<()> {
+    write_lock_file(
+        lock_file,
+        entries
+            .iter()
+            .map(|(id, entry)| (*id, entry.clone()))
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    if lines.is_empty() {
-        anyhow::bail!("Lock file is empty or malformed");
+    fn temp_lock(name: &str) -> String {
+        let dir = std::env::temp_dir().join(format!("jaxon-lock-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(name);
+        std::fs::remove_file(&path).ok();
+        path.to_string_lossy().into_owned()
     }
 
-    let mut keys: Vec<i64> = hashes.keys().copied().collect();
-    keys.sort();
+    fn product(name: &str, id: i64) -> ProductJson {
+        ProductJson {
+            id,
+            ..ProductJson::new(name, "Product")
+        }
+    }
 
-    let mut file =
-        std::fs::File::create(LOCK_FILE).context("Failed to open jaxon.lock for writing")?;
+    #[test]
+    fn v1_entries_unchanged_since_the_sync_are_rehashed() {
+        let unchanged = product("Coins", 1);
+        let synced = product("Gems", 2);
+        let edited = ProductJson {
+            price: Some(99),
+            ..synced.clone()
+        };
+        let off_sale = ProductJson {
+            for_sale: false,
+            ..product("VIP", 3)
+        };
+        let content = format!(
+            "# this file was generated by jaxon\n1={}\n2={}\n3={}\n4=abc\n",
+            get_v1_hash(&unchanged).unwrap(),
+            get_v1_hash(&synced).unwrap(),
+            get_v1_hash(&off_sale).unwrap(),
+        );
 
-    writeln!(file, "{}", lines[0]).context("Failed to write to jaxon.lock")?;
+        let entries = parse_v1(&content, &[unchanged.clone(), edited, off_sale]).unwrap();
 
-    for key in keys {
-        writeln!(file, "{}={}", key, hashes[&key]).context("Failed to write to jaxon.lock")?;
+        assert!(entries[&1].matches(&get_product_hash(&unchanged).unwrap()));
+        assert_eq!(entries[&1].key.as_deref(), Some("Coins"));
+        for id in [2, 3] {
+            assert_eq!(entries[&id].hash_version, 1);
+            assert_eq!(entries[&id].kind, Some(ProductKind::Product));
+        }
+        assert_eq!(entries[&4].kind, None);
     }
 
-    file.flush().context("Failed to flush jaxon.lock")?;
+    #[test]
+    fn v1_lock_is_migrated_without_writing_it() {
+        let lock_file = temp_lock("v1.lock");
+        let content = "1=abc\n";
+        std::fs::write(&lock_file, content).unwrap();
 
-    Ok(())
+        let entries = get_entries(&lock_file, &[]).unwrap();
+        assert_eq!(entries[&1].hash, "abc");
+        assert_eq!(std::fs::read_to_string(&lock_file).unwrap(), content);
+    }
+
+    #[test]
+    fn missing_lock_is_not_created() {
+        let lock_file = temp_lock("missing.lock");
+        assert!(get_entries(&lock_file, &[]).unwrap().is_empty());
+        assert!(!Path::new(&lock_file).exists());
+    }
+
+    #[test]
+    fn v2_lock_round_trips() {
+        let lock_file = temp_lock("v2.lock");
+        let entries = HashMap::from([
+            (
+                1,
+                LockEntry {
+                    shared_id: Some(7),
+                    ..LockEntry::new(
+                        ProductKind::Gamepass,
+                        "vip",
+                        "hash".into(),
+                        Some("image".into()),
+                        Some(42),
+                    )
+                },
+            ),
+            (
+                2,
+                LockEntry::new(ProductKind::Product, "coins", "other".into(), None, None),
+            ),
+        ]);
+
+        write_entries_to_lockfile(&lock_file, &entries).unwrap();
+        let read = get_entries(&lock_file, &[]).unwrap();
+        assert_eq!(
+            serde_json::to_value(&read).unwrap(),
+            serde_json::to_value(&entries).unwrap()
+        );
+    }
 }
@@ -1,22 +1,70 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
 use clap::Parser;
 use cli::{Args, Command};
+use std::path::PathBuf;
 
+mod backend;
 mod cli;
 mod code;
 mod commands;
 mod config;
+mod engine;
+mod files;
+mod journal;
 mod lock;
+mod mock;
+mod plan;
 mod products;
 mod roblox;
+mod schema;
+mod template;
+mod validate;
 
 fn main() -> Result<()> {
+    let mut args = Args::parse();
+    let env = args.env.as_deref();
+    let mut config_file = PathBuf::from(config::TOML_FILE);
+
+    // Every path in jaxon.toml is relative to the project directory
+    if let Some(project) = &args.project {
+        let dir = if project.is_file() || project.extension().is_some_and(|e| e == "toml") {
+            config_file = project.file_name().unwrap_or_default().into();
+            project.parent().unwrap_or(project)
+        } else {
+            project.as_path()
+        };
+
+        // Paths given on the command line stay relative to where jaxon was started
+        let cwd = std::env::current_dir().context("Failed to read the current directory")?;
+        match &mut args.command {
+            Command::Export(export_args) => {
+                export_args.output = export_args.output.as_ref().map(|o| cwd.join(o));
+            }
+            Command::Schema(schema_args) => schema_args.output = cwd.join(&schema_args.output),
+            Command::MockServer(server_args) => {
+                server_args.state = server_args.state.as_ref().map(|s| cwd.join(s));
+            }
+            _ => {}
+        }
+
+        if !dir.as_os_str().is_empty() {
+            std::env::set_current_dir(dir)
+                .with_context(|| format!("Failed to open project {}", project.display()))?;
+        }
+    }
     dotenvy::dotenv().ok();
-    let args = Args::parse();
 
+    let config_file = config_file.as_path();
     match args.command {
-        Command::Sync => commands::sync::run()?,
+        Command::Sync(sync_args) => commands::sync::run(&sync_args, config_file, env)?,
         Command::Init { minimal } => commands::init::run(minimal)?,
+        Command::Pull => commands::pull::run(config_file, env)?,
+        Command::Generate => commands::generate::run(config_file, env)?,
+        Command::Status => commands::status::run(config_file, env)?,
+        Command::Check => commands::check::run(config_file)?,
+        Command::Export(export_args) => commands::export::run(&export_args, config_file, env)?,
+        Command::Schema(schema_args) => commands::schema::run(&schema_args)?,
+        Command::MockServer(args) => commands::mock_server::run(&args)?,
     }
 
     Ok(())
@@ -0,0 +1,393 @@
+use anyhow::{Context, Result};
+use serde_json::{Value, json};
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+use tiny_http::{Header, Method, Request, Response, Server};
+
+use crate::backend::{Backend, MemoryBackend, MemoryState};
+use crate::products::ProductKind;
+use crate::roblox::{Product, RemoteEntry};
+
+const DEFAULT_PAGE_SIZE: usize = 50;
+
+/// Errors the mock server injects to exercise retries
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Faults {
+    /// Answer every Nth request with 429 and `Retry-After: 1`
+    pub rate_limit_every: Option<u64>,
+    /// Answer every Nth request with 503
+    pub fail_every: Option<u64>,
+}
+
+/// The endpoint a request path maps to
+enum Route {
+    Create(ProductKind, i64),
+    Update(ProductKind, i64, i64),
+    Info(ProductKind, i64, i64),
+    List(ProductKind, i64),
+}
+
+fn parse_route(method: &Method, path: &str) -> Option<Route> {
+    let (kind, collection, rest) =
+        if let Some(rest) = path.strip_prefix("/developer-products/v2/universes/") {
+            (ProductKind::Product, "developer-products", rest)
+        } else if let Some(rest) = path.strip_prefix("/game-passes/v1/universes/") {
+            (ProductKind::Gamepass, "game-passes", rest)
+        } else {
+            return None;
+        };
+
+    // {universeId}/{collection}[/...]
+    let segments: Vec<&str> = rest.trim_end_matches('/').split('/').collect();
+    let (universe_id, segments) = match segments.as_slice() {
+        [universe, name, rest @ ..] if *name == collection => (universe.parse().ok()?, rest),
+        _ => return None,
+    };
+
+    match (method, segments) {
+        (Method::Post, []) => Some(Route::Create(kind, universe_id)),
+        (Method::Get, ["creator"]) => Some(Route::List(kind, universe_id)),
+        (Method::Patch, [id]) => Some(Route::Update(kind, universe_id, id.parse().ok()?)),
+        (Method::Get, [id, "creator"]) => Some(Route::Info(kind, universe_id, id.parse().ok()?)),
+        _ => None,
+    }
+}
+
+fn parse_query(query: &str) -> HashMap<String, String> {
+    query
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect()
+}
+
+/// Minimal multipart/form-data parser. Text fields are returned as is, file
+/// fields are returned with an empty value.
+fn parse_multipart(content_type: &str, body: &[u8]) -> Result<HashMap<String, String>> {
+    let boundary = content_type
+        .split(';')
+        .filter_map(|part| part.trim().strip_prefix("boundary="))
+        .next()
+        .context("multipart body without boundary")?
+        .trim_matches('"');
+    let delimiter = format!("--{}", boundary);
+    let body = String::from_utf8_lossy(body);
+
+    let mut fields = HashMap::new();
+    for part in body.split(delimiter.as_str()) {
+        let Some((headers, value)) = part.split_once("\r\n\r\n") else {
+            continue;
+        };
+
+        let Some(name) = headers
+            .split(';')
+            .filter_map(|h| h.trim().strip_prefix("name="))
+            .next()
+        else {
+            continue;
+        };
+        let name = name
+            .split("\r\n")
+            .next()
+            .unwrap_or_default()
+            .trim_matches('"');
+
+        let value = if headers.contains("filename=") {
+            String::new()
+        } else {
+            value.trim_end_matches("\r\n").to_string()
+        };
+        fields.insert(name.to_string(), value);
+    }
+
+    Ok(fields)
+}
+
+fn entry_json(entry: &RemoteEntry) -> Value {
+    let enabled_features: Vec<&str> = if entry.regional_pricing {
+        vec!["RegionalPricing"]
+    } else {
+        vec![]
+    };
+    let price_information = json!({
+        "defaultPriceInRobux": entry.price,
+        "enabledFeatures": enabled_features,
+    });
+
+    match entry.kind {
+        ProductKind::Product => json!({
+            "productId": entry.id,
+            "name": entry.name,
+            "description": entry.description,
+            "iconImageAssetId": entry.icon_asset_id,
+            "priceInformation": price_information,
+            "isForSale": entry.for_sale,
+        }),
+        ProductKind::Gamepass => json!({
+            "gamePassId": entry.id,
+            "name": entry.name,
+            "description": entry.description,
+            "iconAssetId": entry.icon_asset_id,
+            "priceInformation": price_information,
+            "isForSale": entry.for_sale,
+        }),
+    }
+}
+
+/// Builds the full product from a (possibly partial) form on top of `current`
+fn product_from_fields(
+    current: Option<&RemoteEntry>,
+    fields: &HashMap<String, String>,
+) -> Result<Product, String> {
+    let text = |key: &str, fallback: Option<&String>| {
+        fields.get(key).or(fallback).cloned().unwrap_or_default()
+    };
+    let flag = |key: &str, fallback: bool| fields.get(key).map_or(fallback, |v| v == "true");
+
+    let price = match fields.get("price") {
+        Some(price) => Some(
+            price
+                .parse()
+                .map_err(|_| format!("invalid price {:?}", price))?,
+        ),
+        None => current.and_then(|c| c.price),
+    };
+
+    Ok(Product {
+        name: text("name", current.map(|c| &c.name)),
+        description: text("description", current.map(|c| &c.description)),
+        // The contents are never looked at, any non-empty value counts as an upload
+        image_file: if fields.contains_key("imageFile") {
+            "upload".into()
+        } else {
+            String::new()
+        },
+        price,
+        regional_pricing: flag(
+            "isRegionalPricingEnabled",
+            current.is_some_and(|c| c.regional_pricing),
+        ),
+        for_sale: flag("isForSale", current.is_none_or(|c| c.for_sale)),
+    })
+}
+
+fn json_response(status: u16, body: &Value) -> Response<std::io::Cursor<Vec<u8>>> {
+    let header = Header::from_bytes(&b"Content-Type"[..], &b"application/json"[..]).unwrap();
+    Response::from_string(body.to_string())
+        .with_status_code(status)
+        .with_header(header)
+}
+
+fn error_response(status: u16, message: &str) -> Response<std::io::Cursor<Vec<u8>>> {
+    json_response(status, &json!({ "code": status, "message": message }))
+}
+
+pub struct MockServer {
+    universes: BTreeMap<i64, MemoryBackend>,
+    state_file: Option<PathBuf>,
+    faults: Faults,
+    requests: u64,
+}
+
+impl MockServer {
+    pub fn new(state_file: Option<PathBuf>, faults: Faults) -> Result<Self> {
+        let states: BTreeMap<i64, MemoryState> = match &state_file {
+            Some(path) if path.exists() => {
+                let content = std::fs::read_to_string(path)
+                    .with_context(|| format!("Failed to read {}", path.display()))?;
+                serde_json::from_str(&content)
+                    .with_context(|| format!("Failed to parse {}", path.display()))?
+            }
+            _ => BTreeMap::new(),
+        };
+
+        Ok(MockServer {
+            universes: states
+                .into_iter()
+                .map(|(id, state)| (id, MemoryBackend::from_state(state)))
+                .collect(),
+            state_file,
+            faults,
+            requests: 0,
+        })
+    }
+
+    fn save(&self) -> Result<()> {
+        let Some(path) = &self.state_file else {
+            return Ok(());
+        };
+        let states: BTreeMap<i64, MemoryState> = self
+            .universes
+            .iter()
+            .map(|(id, backend)| (*id, backend.state()))
+            .collect();
+        let data =
+            serde_json::to_string_pretty(&states).context("Failed to serialize mock state")?;
+        std::fs::write(path, data).with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    pub fn serve(mut self, port: u16) -> Result<()> {
+        let server = Server::http(("127.0.0.1", port))
+            .map_err(|e| anyhow::anyhow!("Failed to start mock server: {}", e))?;
+        println!(
+            "Mock Open Cloud server listening on http://127.0.0.1:{}",
+            port
+        );
+
+        // A client hanging up or a failed state write only affects that request
+        for request in server.incoming_requests() {
+            if let Err(e) = self.handle(request) {
+                eprintln!("Error: {:#}", e);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn handle(&mut self, mut request: Request) -> Result<()> {
+        self.requests += 1;
+        let method = request.method().clone();
+        let url = request.url().to_string();
+
+        if let Some(n) = self.faults.rate_limit_every
+            && n > 0
+            && self.requests.is_multiple_of(n)
+        {
+            println!("{} {} -> 429 (injected)", method, url);
+            let retry_after = Header::from_bytes(&b"Retry-After"[..], &b"1"[..]).unwrap();
+            let response = error_response(429, "Too many requests").with_header(retry_after);
+            return request.respond(response).context("Failed to respond");
+        }
+        if let Some(n) = self.faults.fail_every
+            && n > 0
+            && self.requests.is_multiple_of(n)
+        {
+            println!("{} {} -> 503 (injected)", method, url);
+            return request
+                .respond(error_response(503, "Service unavailable"))
+                .context("Failed to respond");
+        }
+
+        let content_type = request
+            .headers()
+            .iter()
+            .find(|h| h.field.equiv("Content-Type"))
+            .map(|h| h.value.to_string())
+            .unwrap_or_default();
+        let mut body = Vec::new();
+        request
+            .as_reader()
+            .read_to_end(&mut body)
+            .context("Failed to read request body")?;
+
+        let (path, query) = url.split_once('?').unwrap_or((&url, ""));
+        let response = match parse_route(&method, path) {
+            Some(route) => match self.route(route, &content_type, &body, query) {
+                Ok(response) => response,
+                Err(e) => {
+                    eprintln!("Error: {:#}", e);
+                    error_response(500, &format!("{:#}", e))
+                }
+            },
+            None => error_response(404, "Not found"),
+        };
+
+        println!("{} {} -> {}", method, url, response.status_code().0);
+        request.respond(response).context("Failed to respond")
+    }
+
+    fn route(
+        &mut self,
+        route: Route,
+        content_type: &str,
+        body: &[u8],
+        query: &str,
+    ) -> Result<Response<std::io::Cursor<Vec<u8>>>> {
+        let response = match route {
+            Route::Create(kind, universe_id) => {
+                let fields = match parse_multipart(content_type, body) {
+                    Ok(fields) => fields,
+                    Err(e) => return Ok(error_response(400, &e.to_string())),
+                };
+                let data = match product_from_fields(None, &fields) {
+                    Ok(data) => data,
+                    Err(message) => return Ok(error_response(400, &message)),
+                };
+
+                let backend = self.universes.entry(universe_id).or_default();
+                match backend.create(kind, &data) {
+                    Ok(entry) => {
+                        self.save()?;
+                        json_response(200, &entry_json(&entry))
+                    }
+                    Err(e) => error_response(400, &e.to_string()),
+                }
+            }
+            Route::Update(kind, universe_id, id) => {
+                let fields = match parse_multipart(content_type, body) {
+                    Ok(fields) => fields,
+                    Err(e) => return Ok(error_response(400, &e.to_string())),
+                };
+                let Some(current) = self.get(kind, universe_id, id) else {
+                    return Ok(error_response(404, "Not found"));
+                };
+                let data = match product_from_fields(Some(&current), &fields) {
+                    Ok(data) => data,
+                    Err(message) => return Ok(error_response(400, &message)),
+                };
+
+                let backend = self.universes.entry(universe_id).or_default();
+                match backend.update(kind, id, &data) {
+                    Ok(_) => {
+                        self.save()?;
+                        Response::from_data(Vec::new()).with_status_code(204)
+                    }
+                    Err(e) => error_response(400, &e.to_string()),
+                }
+            }
+            Route::Info(kind, universe_id, id) => match self.get(kind, universe_id, id) {
+                Some(entry) => json_response(200, &entry_json(&entry)),
+                None => error_response(404, "Not found"),
+            },
+            Route::List(kind, universe_id) => {
+                let query = parse_query(query);
+                let page_size = query
+                    .get("pageSize")
+                    .and_then(|v| v.parse().ok())
+                    .unwrap_or(DEFAULT_PAGE_SIZE)
+                    .max(1);
+                // Page tokens are the last ID of the previous page
+                let after: i64 = query
+                    .get("pageToken")
+                    .and_then(|v| v.parse().ok())
+                    .unwrap_or(0);
+
+                let mut items: Vec<RemoteEntry> = match self.universes.get(&universe_id) {
+                    Some(backend) => backend.list(kind)?,
+                    None => vec![],
+                };
+                items.retain(|e| e.id > after);
+                let has_more = items.len() > page_size;
+                items.truncate(page_size);
+
+                let next_page_token = match (has_more, items.last()) {
+                    (true, Some(last)) => Some(last.id.to_string()),
+                    _ => None,
+                };
+                let list: Vec<Value> = items.iter().map(entry_json).collect();
+                let key = match kind {
+                    ProductKind::Product => "developerProducts",
+                    ProductKind::Gamepass => "gamePasses",
+                };
+
+                json_response(200, &json!({ key: list, "nextPageToken": next_page_token }))
+            }
+        };
+
+        Ok(response)
+    }
+
+    fn get(&self, kind: ProductKind, universe_id: i64, id: i64) -> Option<RemoteEntry> {
+        self.universes.get(&universe_id)?.get(kind, id).ok()
+    }
+}
@@ -0,0 +1,513 @@
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+
+use crate::backend::Backend;
+use crate::config::Config;
+use crate::lock::{self, LockEntry};
+use crate::products::{ProductJson, ProductKind};
+use crate::roblox::{self, RemoteEntry};
+use crate::validate::Problem;
+
+#[derive(Debug, Clone)]
+pub struct FieldChange {
+    pub field: &'static str,
+    pub from: String,
+    pub to: String,
+}
+
+#[derive(Debug, Clone)]
+pub enum Action {
+    Create,
+    /// `metadata` and `icon` say what changed since the last sync. `changes` is
+    /// only filled in once the remote state has been fetched.
+    Update {
+        metadata: bool,
+        icon: bool,
+        changes: Vec<FieldChange>,
+    },
+    Skip,
+    Invalid(String),
+    /// Tracked, but deleted on Roblox. Only found by a dry run.
+    Missing,
+}
+
+#[derive(Debug, Clone)]
+pub struct PlannedEntry {
+    pub index: usize,
+    pub kind: Option<ProductKind>,
+    pub hash: String,
+    pub image_hash: Option<String>,
+    pub action: Action,
+}
+
+impl PlannedEntry {
+    pub fn is_pending(&self) -> bool {
+        matches!(self.action, Action::Create | Action::Update { .. })
+    }
+
+    /// Whether the image has to be sent with the request
+    pub fn uploads_icon(&self) -> bool {
+        matches!(
+            self.action,
+            Action::Create | Action::Update { icon: true, .. }
+        )
+    }
+
+    /// What an update changes, e.g. "metadata, icon"
+    pub fn updated_parts(&self) -> &'static str {
+        match self.action {
+            Action::Update {
+                metadata: true,
+                icon: true,
+                ..
+            } => "metadata, icon",
+            Action::Update { icon: true, .. } => "icon",
+            _ => "metadata",
+        }
+    }
+}
+
+pub fn build_plan(
+    products: &[ProductJson],
+    locked: &HashMap<i64, LockEntry>,
+) -> Result<Vec<PlannedEntry>> {
+    let mut plan = Vec::with_capacity(products.len());
+
+    for (index, product) in products.iter().enumerate() {
+        let hash = lock::get_product_hash(product)?;
+        let image_hash = lock::get_image_hash(&product.image)?;
+        let kind = product.kind();
+
+        let action = match kind {
+            None => Action::Invalid(format!("unknown type {:?}", product.product_type)),
+            Some(_) if product.id <= 0 => Action::Create,
+            Some(_) => {
+                let existing = locked.get(&product.id);
+                let metadata = !existing.is_some_and(|e| e.matches(&hash));
+                // A missing image can't be uploaded, so it never counts as an icon change
+                let icon = image_hash.is_some()
+                    && existing.and_then(|e| e.image_hash.as_ref()) != image_hash.as_ref();
+
+                if metadata || icon {
+                    Action::Update {
+                        metadata,
+                        icon,
+                        changes: vec![],
+                    }
+                } else {
+                    Action::Skip
+                }
+            }
+        };
+
+        plan.push(PlannedEntry {
+            index,
+            kind,
+            hash,
+            image_hash,
+            action,
+        });
+    }
+
+    Ok(plan)
+}
+
+/// Marks the entries with problems as invalid, so a dry run lists them with
+/// the rest of the plan
+pub fn mark_invalid(plan: &mut [PlannedEntry], problems: &[Problem]) {
+    for entry in plan.iter_mut() {
+        let reasons: Vec<String> = problems
+            .iter()
+            .filter(|p| p.index == entry.index)
+            .map(|p| format!("{} {}", p.field, p.message))
+            .collect();
+        if !reasons.is_empty() {
+            entry.action = Action::Invalid(reasons.join("; "));
+        }
+    }
+}
+
+/// Gives entries without a `productId` the ID the lock tracks for them, so
+/// they are updated instead of created again. Entries are matched on the
+/// products.json ID recorded in an environment lock first, which survives
+/// renames, then on key and type. Returns how many entries were matched.
+pub fn match_keys(products: &mut [ProductJson], locked: &HashMap<i64, LockEntry>) -> usize {
+    let by_shared_id = |product: &ProductJson, entry: &LockEntry| {
+        product.shared_id.is_some() && entry.shared_id == product.shared_id
+    };
+    let by_key =
+        |product: &ProductJson, entry: &LockEntry| entry.key.as_deref() == Some(product.key());
+
+    match_by(products, locked, by_shared_id) + match_by(products, locked, by_key)
+}
+
+fn match_by(
+    products: &mut [ProductJson],
+    locked: &HashMap<i64, LockEntry>,
+    is_match: impl Fn(&ProductJson, &LockEntry) -> bool,
+) -> usize {
+    let mut matched = 0;
+
+    for index in 0..products.len() {
+        let product = &products[index];
+        if product.id > 0 {
+            continue;
+        }
+        let Some(kind) = product.kind() else {
+            continue;
+        };
+
+        let found = locked.iter().find(|(id, entry)| {
+            entry.kind == Some(kind)
+                && is_match(product, entry)
+                && !products.iter().any(|p| p.id == **id)
+        });
+
+        if let Some((id, _)) = found {
+            products[index].id = *id;
+            matched += 1;
+        }
+    }
+
+    matched
+}
+
+/// Fills in the IDs of the selected environment. Environments keep their IDs
+/// in their own lock, so the ones in products.json are moved to `shared_id`
+/// and replaced by the IDs tracked there.
+pub fn resolve_ids(
+    config: &Config,
+    products: &mut [ProductJson],
+    locked: &HashMap<i64, LockEntry>,
+) -> usize {
+    if config.environment.is_some() {
+        for product in products.iter_mut() {
+            product.shared_id = Some(product.id).filter(|id| *id > 0);
+            product.id = 0;
+        }
+    }
+
+    match_keys(products, locked)
+}
+
+/// Entries tracked in the lock that no longer exist in products.json, sorted by ID
+pub fn find_removed(
+    products: &[ProductJson],
+    locked: &HashMap<i64, LockEntry>,
+) -> Vec<(i64, LockEntry)> {
+    let mut removed: Vec<(i64, LockEntry)> = locked
+        .iter()
+        .filter(|(id, _)| !products.iter().any(|p| p.id == **id))
+        .map(|(id, entry)| (*id, entry.clone()))
+        .collect();
+    removed.sort_by_key(|(id, _)| *id);
+    removed
+}
+
+pub fn print_removed(removed: &[(i64, LockEntry)]) {
+    for (id, entry) in removed {
+        match entry.kind {
+            Some(kind) => println!("  - retire   {} #{}", kind.as_str(), id),
+            None => println!("  ? unknown  #{} (type not recorded, can't retire)", id),
+        }
+    }
+}
+
+/// Compares the fields jaxon manages between a local entry and its live state
+pub fn diff(local: &ProductJson, remote: &RemoteEntry) -> Vec<FieldChange> {
+    let mut changes = vec![];
+
+    if local.name != remote.name {
+        changes.push(FieldChange {
+            field: "name",
+            from: format!("{:?}", remote.name),
+            to: format!("{:?}", local.name),
+        });
+    }
+    if local.description != remote.description {
+        changes.push(FieldChange {
+            field: "description",
+            from: format!("{:?}", remote.description),
+            to: format!("{:?}", local.description),
+        });
+    }
+    // Without a price the live one is kept, so there is nothing to compare
+    if local.price.is_some() && local.price != remote.price {
+        let show = |price: Option<i64>| price.map_or("none".into(), |p| p.to_string());
+        changes.push(FieldChange {
+            field: "price",
+            from: show(remote.price),
+            to: show(local.price),
+        });
+    }
+    if local.for_sale != remote.for_sale {
+        changes.push(FieldChange {
+            field: "forSale",
+            from: remote.for_sale.to_string(),
+            to: local.for_sale.to_string(),
+        });
+    }
+    if local.regional_pricing != remote.regional_pricing {
+        changes.push(FieldChange {
+            field: "regionalPricing",
+            from: remote.regional_pricing.to_string(),
+            to: local.regional_pricing.to_string(),
+        });
+    }
+
+    changes
+}
+
+/// Fills in the changed fields of every update by reading the live state, and
+/// marks updates of entries deleted on Roblox as missing. Only read endpoints
+/// are called.
+pub fn fetch_changes(
+    plan: &mut [PlannedEntry],
+    products: &[ProductJson],
+    backend: &dyn Backend,
+) -> Result<()> {
+    for entry in plan.iter_mut() {
+        let Action::Update { changes, .. } = &mut entry.action else {
+            continue;
+        };
+        let Some(kind) = entry.kind else {
+            continue;
+        };
+
+        let product = &products[entry.index];
+        match backend.get(kind, product.id) {
+            Ok(remote) => *changes = diff(product, &remote),
+            Err(e) if roblox::is_not_found(&e) => entry.action = Action::Missing,
+            Err(e) => {
+                return Err(e).with_context(|| {
+                    format!("Failed to fetch {} {:?}", product.location(), product.name)
+                });
+            }
+        }
+    }
+
+    Ok(())
+}
+
+pub fn print_plan(plan: &[PlannedEntry], products: &[ProductJson]) {
+    print!("{}", render_plan(plan, products));
+}
+
+/// The plan as printed by `sync --dry-run`, one line per entry and field
+fn render_plan(plan: &[PlannedEntry], products: &[ProductJson]) -> String {
+    let mut lines = Vec::new();
+    let (mut create, mut update, mut skip, mut invalid, mut missing) = (0, 0, 0, 0, 0);
+
+    for entry in plan {
+        let product = &products[entry.index];
+        let label = format!(
+            "{} {} {:?}",
+            product.location(),
+            product.product_type,
+            product.name
+        );
+
+        match &entry.action {
+            Action::Create => {
+                create += 1;
+                lines.push(format!("  + create   {}", label));
+            }
+            Action::Update {
+                metadata,
+                icon,
+                changes,
+            } => {
+                update += 1;
+                lines.push(format!(
+                    "  ~ update   {} (#{}) [{}]",
+                    label,
+                    product.id,
+                    entry.updated_parts()
+                ));
+                if *metadata && changes.is_empty() {
+                    lines.push("      (no field differences from live state)".into());
+                }
+                for change in changes {
+                    lines.push(format!(
+                        "      {}: {} -> {}",
+                        change.field, change.from, change.to
+                    ));
+                }
+                if *icon {
+                    lines.push(format!("      image: {} changed", product.image));
+                }
+            }
+            Action::Skip => {
+                skip += 1;
+                lines.push(format!("  = skip     {} (#{})", label, product.id));
+            }
+            Action::Invalid(reason) => {
+                invalid += 1;
+                lines.push(format!("  ! invalid  {}: {}", label, reason));
+            }
+            Action::Missing => {
+                missing += 1;
+                lines.push(format!(
+                    "  ? missing  {} (#{}) was deleted on Roblox, remove its productId to create it again",
+                    label, product.id
+                ));
+            }
+        }
+    }
+
+    lines.push(format!(
+        "Plan: {} to create, {} to update, {} unchanged, {} invalid, {} missing on Roblox",
+        create, update, skip, invalid, missing
+    ));
+    lines.join("\n") + "\n"
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::MemoryBackend;
+    use crate::roblox::Product;
+
+    fn tracked(product: &ProductJson) -> LockEntry {
+        let hash = lock::get_product_hash(product).unwrap();
+        LockEntry::new(product.kind().unwrap(), product.key(), hash, None, None)
+    }
+
+    fn remote(product: &ProductJson) -> Product {
+        Product {
+            name: product.name.clone(),
+            description: product.description.clone(),
+            image_file: String::new(),
+            price: product.price,
+            regional_pricing: product.regional_pricing,
+            for_sale: product.for_sale,
+        }
+    }
+
+    #[test]
+    fn build_plan_picks_an_action_per_entry() {
+        let unchanged = ProductJson {
+            id: 1,
+            ..ProductJson::new("Coins", "Product")
+        };
+        let edited = ProductJson {
+            id: 2,
+            ..ProductJson::new("Gems", "Product")
+        };
+        let products = vec![
+            ProductJson::new("New", "Gamepass"),
+            unchanged.clone(),
+            ProductJson {
+                price: Some(99),
+                ..edited.clone()
+            },
+            ProductJson::new("Bundle", "Bundle"),
+        ];
+        let locked = HashMap::from([(1, tracked(&unchanged)), (2, tracked(&edited))]);
+
+        let plan = build_plan(&products, &locked).unwrap();
+        assert!(matches!(plan[0].action, Action::Create));
+        assert!(matches!(plan[1].action, Action::Skip));
+        assert!(matches!(
+            plan[2].action,
+            Action::Update {
+                metadata: true,
+                icon: false,
+                ..
+            }
+        ));
+        assert!(matches!(plan[3].action, Action::Invalid(_)));
+        assert_eq!(
+            plan.iter().filter(|e| e.is_pending()).count(),
+            2,
+            "only creates and updates are pushed"
+        );
+    }
+
+    #[test]
+    fn diff_lists_changed_fields() {
+        let local = ProductJson {
+            id: 3,
+            price: Some(20),
+            for_sale: false,
+            ..ProductJson::new("Coins", "Product")
+        };
+        let live = RemoteEntry {
+            kind: ProductKind::Product,
+            id: 3,
+            name: "Coins".into(),
+            description: String::new(),
+            price: Some(10),
+            regional_pricing: false,
+            for_sale: true,
+            icon_asset_id: None,
+        };
+
+        let changes = diff(&local, &live);
+        let fields: Vec<&str> = changes.iter().map(|c| c.field).collect();
+        assert_eq!(fields, ["price", "forSale"]);
+        assert_eq!(
+            (changes[0].from.as_str(), changes[0].to.as_str()),
+            ("10", "20")
+        );
+
+        // Without a local price the live one is kept
+        let no_price = ProductJson {
+            price: None,
+            ..local
+        };
+        assert_eq!(diff(&no_price, &live).len(), 1);
+    }
+
+    #[test]
+    fn dry_run_reports_entries_deleted_on_roblox() {
+        let backend = MemoryBackend::default();
+        let coins = ProductJson::new("Coins", "Product");
+        let id = backend
+            .create(ProductKind::Product, &remote(&coins))
+            .unwrap()
+            .id;
+
+        let products = vec![
+            ProductJson {
+                id,
+                price: Some(15),
+                ..coins
+            },
+            ProductJson {
+                id: 999,
+                ..ProductJson::new("Gems", "Product")
+            },
+        ];
+        let mut plan = build_plan(&products, &HashMap::new()).unwrap();
+        fetch_changes(&mut plan, &products, &backend).unwrap();
+
+        assert!(matches!(plan[1].action, Action::Missing));
+        assert_eq!(
+            render_plan(&plan, &products),
+            format!(
+                "  ~ update   new entry Product \"Coins\" (#{id}) [metadata]
+      price: 10 -> 15
+  ? missing  new entry Product \"Gems\" (#999) was deleted on Roblox, remove its productId to create it again
+Plan: 0 to create, 1 to update, 0 unchanged, 0 invalid, 1 missing on Roblox
+"
+            )
+        );
+    }
+
+    #[test]
+    fn find_removed_lists_untracked_ids_in_order() {
+        let products = vec![ProductJson {
+            id: 2,
+            ..ProductJson::new("Coins", "Product")
+        }];
+        let entry = |name: &str| tracked(&ProductJson::new(name, "Product"));
+        let locked = HashMap::from([(3, entry("Gone")), (2, entry("Coins")), (1, entry("Old"))]);
+
+        let ids: Vec<i64> = find_removed(&products, &locked)
+            .iter()
+            .map(|(id, _)| *id)
+            .collect();
+        assert_eq!(ids, [1, 3]);
+    }
+}
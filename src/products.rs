@@ -1,36 +1,528 @@
 use anyhow::{Context, Result};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+
+use crate::{files, schema};
 
 pub const PRODUCT_FILE: &str = "products.json";
 
-#[derive(Deserialize, Serialize, Debug, Clone)]
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ProductKind {
+    Product,
+    Gamepass,
+}
+
+impl ProductKind {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "Product" => Some(ProductKind::Product),
+            "Gamepass" => Some(ProductKind::Gamepass),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ProductKind::Product => "Product",
+            ProductKind::Gamepass => "Gamepass",
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Clone)]
 #[serde[rename_all = "camelCase"]]
 pub struct ProductJson {
+    /// Stable identifier used as the codegen key. Defaults to `name`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key: Option<String>,
+    /// Name players see
     pub name: String,
     pub description: String,
+    /// Either "Product" or "Gamepass"
     #[serde(rename = "type")]
+    #[schemars(with = "ProductKind")]
     pub product_type: String,
+    /// Path of the icon image, relative to the project
     pub image: String,
-    pub price: i64,
+    /// Price in Robux. Can be left out while the entry is off sale.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub price: Option<i64>,
     #[serde(rename = "regionalPricing")]
     pub regional_pricing: bool,
-    #[serde(rename = "productId")]
+    /// Assigned by jaxon. Include it when modifying an existing product.
+    #[serde(rename = "productId", default)]
     pub id: i64,
+    /// Set to false to keep the entry off sale
+    #[serde(default = "default_for_sale", skip_serializing_if = "is_true")]
+    pub for_sale: bool,
+    /// Labels for grouping entries in generated code and exports. Not sent to Roblox.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Custom fields passed to templates. Not sent to Roblox.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub metadata: BTreeMap<String, serde_json::Value>,
+    /// Where the entry was read from
+    #[serde(skip)]
+    pub source: Option<Source>,
+    /// `productId` from products.json while an environment's IDs are in use
+    #[serde(skip)]
+    pub shared_id: Option<i64>,
+}
+
+#[cfg(test)]
+impl ProductJson {
+    /// Untracked entry for test fixtures, for sale at 10 Robux. Tests change
+    /// the fields they care about with struct update syntax.
+    pub fn new(name: &str, product_type: &str) -> Self {
+        ProductJson {
+            key: None,
+            name: name.into(),
+            description: String::new(),
+            product_type: product_type.into(),
+            image: String::new(),
+            price: Some(10),
+            regional_pricing: false,
+            id: 0,
+            for_sale: true,
+            tags: Vec::new(),
+            metadata: BTreeMap::new(),
+            source: None,
+            shared_id: None,
+        }
+    }
+}
+
+/// Catalog file and position within it an entry was read from
+#[derive(Debug, Clone)]
+pub struct Source {
+    pub file: PathBuf,
+    pub index: usize,
+}
+
+/// products.json is either a plain array of entries, or an object holding them
+/// under "products", which lets editors pick up the schema from "$schema".
+#[derive(Deserialize, Serialize, JsonSchema)]
+#[serde(untagged)]
+pub enum ProductFile {
+    Document(ProductDocument),
+    List(Vec<ProductJson>),
+}
+
+#[derive(Deserialize, Serialize, JsonSchema)]
+pub struct ProductDocument {
+    #[serde(rename = "$schema", default, skip_serializing_if = "Option::is_none")]
+    pub schema: Option<String>,
+    pub products: Vec<ProductJson>,
+}
+
+fn default_for_sale() -> bool {
+    true
+}
+
+pub fn is_true(value: &bool) -> bool {
+    *value
+}
+
+impl ProductJson {
+    pub fn kind(&self) -> Option<ProductKind> {
+        ProductKind::parse(&self.product_type)
+    }
+
+    pub fn key(&self) -> &str {
+        self.key.as_deref().unwrap_or(&self.name)
+    }
+
+    /// Where the entry is defined, e.g. `products/passes.toml[2]`
+    pub fn location(&self) -> String {
+        match &self.source {
+            Some(source) => format!("{}[{}]", source.file.display(), source.index),
+            None => "new entry".into(),
+        }
+    }
 }
 
-pub fn read_products() -> Result<Vec<ProductJson>> {
-    let content = std::fs::read_to_string("products.json")
-        .context("Failed to open products.json. Did you run `jaxon init`?")?;
+/// Fails when two entries of the same type share a key, since they would
+/// overwrite each other in the generated code.
+pub fn check_keys(products: &[ProductJson]) -> Result<()> {
+    let mut seen: HashMap<(&str, &str), usize> = HashMap::new();
 
-    let products: Vec<ProductJson> =
-        serde_json::from_str(&content).context("Failed to parse products.json")?;
+    for (index, product) in products.iter().enumerate() {
+        if let Some(first) = seen.insert((product.product_type.as_str(), product.key()), index) {
+            anyhow::bail!(
+                "Entries {} and {} share the key {:?}. Give one of them a unique \"key\".",
+                products[first].location(),
+                product.location(),
+                product.key()
+            );
+        }
+    }
+
+    Ok(())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Json,
+    Toml,
+    Yaml,
+}
+
+impl Format {
+    fn of(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()? {
+            "json" => Some(Format::Json),
+            "toml" => Some(Format::Toml),
+            "yaml" | "yml" => Some(Format::Yaml),
+            _ => None,
+        }
+    }
+}
+
+/// The contents of one catalog file
+struct CatalogFile {
+    format: Format,
+    /// `Some` with the "$schema" value when the entries are under "products"
+    layout: Option<Option<String>>,
+    products: Vec<ProductJson>,
+}
+
+fn is_glob(pattern: &str) -> bool {
+    pattern.contains(['*', '?', '['])
+}
+
+/// Every file of the catalog `pattern` that exists, in the order entries are
+/// read. The pattern is a file, a directory or a glob such as `products/*.json`.
+pub fn catalog_files(pattern: &str) -> Result<Vec<PathBuf>> {
+    let path = Path::new(pattern);
+
+    let mut files: Vec<PathBuf> = if path.is_dir() {
+        std::fs::read_dir(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|file| file.is_file() && Format::of(file).is_some())
+            .collect()
+    } else if is_glob(pattern) {
+        glob::glob(pattern)
+            .with_context(|| format!("Invalid catalog pattern {:?}", pattern))?
+            .filter_map(|entry| entry.ok())
+            .filter(|file| file.is_file() && Format::of(file).is_some())
+            .collect()
+    } else if path.exists() {
+        vec![path.to_path_buf()]
+    } else {
+        vec![]
+    };
+
+    files.sort();
+    Ok(files)
+}
+
+/// File that entries without a source, such as newly pulled ones, are added to
+fn default_file(pattern: &str) -> Result<PathBuf> {
+    if let Some(first) = catalog_files(pattern)?.into_iter().next() {
+        return Ok(first);
+    }
+
+    let path = Path::new(pattern);
+    if path.is_dir() {
+        Ok(path.join(PRODUCT_FILE))
+    } else if is_glob(pattern) {
+        anyhow::bail!(
+            "No catalog file matches {:?} to add new entries to",
+            pattern
+        )
+    } else {
+        Ok(path.to_path_buf())
+    }
+}
+
+fn parse_file(path: &Path, content: &str) -> Result<CatalogFile> {
+    let format = Format::of(path).with_context(|| {
+        format!(
+            "Unsupported catalog file {}, expected .json, .toml, .yaml or .yml",
+            path.display()
+        )
+    })?;
+    let error = || format!("Failed to parse {}", path.display());
+
+    // Parsed by layout rather than as ProductFile to keep serde's error messages
+    let (layout, products) = match format {
+        Format::Json if content.trim_start().starts_with('{') => {
+            let document: ProductDocument = serde_json::from_str(content).with_context(error)?;
+            (Some(document.schema), document.products)
+        }
+        Format::Json => (None, serde_json::from_str(content).with_context(error)?),
+        Format::Toml => {
+            let document: ProductDocument = toml::from_str(content).with_context(error)?;
+            (Some(document.schema), document.products)
+        }
+        Format::Yaml => {
+            let value: serde_norway::Value = serde_norway::from_str(content).with_context(error)?;
+            if value.is_mapping() {
+                let document: ProductDocument =
+                    serde_norway::from_value(value).with_context(error)?;
+                (Some(document.schema), document.products)
+            } else {
+                (None, serde_norway::from_value(value).with_context(error)?)
+            }
+        }
+    };
+
+    Ok(CatalogFile {
+        format,
+        layout,
+        products,
+    })
+}
+
+fn read_file(path: &Path) -> Result<CatalogFile> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    let mut file = parse_file(path, &content)?;
+
+    for (index, product) in file.products.iter_mut().enumerate() {
+        product.source = Some(Source {
+            file: path.to_path_buf(),
+            index,
+        });
+    }
+    Ok(file)
+}
 
+fn serialize_file(file: &CatalogFile) -> Result<String> {
+    let products = file.products.clone();
+    let data = match (file.format, file.layout.clone()) {
+        // TOML has no top-level arrays, and uses a `#:schema` comment instead of "$schema"
+        (Format::Toml, _) => toml::to_string_pretty(&ProductDocument {
+            schema: None,
+            products,
+        })?,
+        (format, layout) => {
+            let document = match layout {
+                Some(schema) => ProductFile::Document(ProductDocument { schema, products }),
+                None => ProductFile::List(products),
+            };
+            if format == Format::Yaml {
+                serde_norway::to_string(&document)?
+            } else {
+                serde_json::to_string_pretty(&document)?
+            }
+        }
+    };
+    Ok(data)
+}
+
+/// Sets the `productId` of every `[[products]]` table, keeping comments and
+/// formatting
+fn patch_toml(content: &str, ids: &[i64]) -> Option<String> {
+    let mut document: toml_edit::DocumentMut = content.parse().ok()?;
+    let tables = document.get_mut("products")?.as_array_of_tables_mut()?;
+
+    for (table, id) in tables.iter_mut().zip(ids) {
+        match table.get_mut("productId") {
+            Some(item) => {
+                let value = item.as_value_mut()?;
+                let decor = value.decor().clone();
+                *value = (*id).into();
+                *value.decor_mut() = decor;
+            }
+            None => {
+                table.insert("productId", toml_edit::value(*id));
+            }
+        }
+    }
+    Some(document.to_string())
+}
+
+/// Sets the `productId` of every entry of the top-level list, line by line.
+/// There is no maintained YAML library that keeps comments. Only block-style
+/// entries are patched, anything else such as flow mappings returns `None`,
+/// and the result is checked by `patch_ids` anyway.
+fn patch_yaml(content: &str, ids: &[i64]) -> Option<String> {
+    const KEYS: [&str; 3] = ["productId:", "\"productId\":", "'productId':"];
+
+    let mut lines: Vec<String> = content.lines().map(String::from).collect();
+    let indent_of = |line: &str| line.len() - line.trim_start().len();
+    let is_item = |line: &str| line.trim_start().starts_with("- ");
+    let is_content = |line: &str| !line.trim().is_empty() && !line.trim_start().starts_with('#');
+
+    // Entries start with "- " at the indentation of the first one
+    let first = lines.iter().position(|line| is_item(line))?;
+    let indent = indent_of(&lines[first]);
+    let starts: Vec<usize> = (first..lines.len())
+        .filter(|&i| is_item(&lines[i]) && indent_of(&lines[i]) == indent)
+        .collect();
+    if starts.len() != ids.len() {
+        return None;
+    }
+
+    // Go backwards so inserted lines don't move the entries still to patch
+    for (i, (&start, id)) in starts.iter().zip(ids).enumerate().rev() {
+        let end = starts.get(i + 1).copied().unwrap_or(lines.len());
+        let after_dash = &lines[start][indent + 2..];
+        if after_dash.trim_start().starts_with('{') {
+            return None;
+        }
+        let column = indent + 2 + indent_of(after_dash);
+
+        let found = (start..end).find_map(|j| {
+            let line = &lines[j];
+            if j != start && indent_of(line) != column {
+                return None;
+            }
+            let rest = &line[column.min(line.len())..];
+            KEYS.iter()
+                .find(|key| rest.starts_with(*key))
+                .map(|key| (j, column + key.len()))
+        });
+
+        match found {
+            Some((j, key_end)) => {
+                let (key, value) = lines[j].split_at(key_end);
+                // Keep whatever follows the old ID, such as a comment
+                let value = value.trim_start();
+                let rest = &value[value.find(char::is_whitespace).unwrap_or(value.len())..];
+                lines[j] = format!("{} {}{}", key, id, rest);
+            }
+            None => {
+                // After the last line of the entry, so nested values stay intact
+                let last = (start..end).rev().find(|&j| is_content(&lines[j]))?;
+                lines.insert(last + 1, format!("{}productId: {}", " ".repeat(column), id));
+            }
+        }
+    }
+
+    let mut data = lines.join("\n");
+    if content.ends_with('\n') {
+        data.push('\n');
+    }
+    Some(data)
+}
+
+/// Writes only the new IDs into `content`, so TOML and YAML files keep their
+/// comments. Returns `None` when anything else changed, or when the patched
+/// file doesn't read back as `products`, and the file is serialized again.
+fn patch_ids(
+    path: &Path,
+    content: &str,
+    current: &CatalogFile,
+    products: &[ProductJson],
+) -> Result<Option<String>> {
+    let without_ids = |products: &[ProductJson]| {
+        let products: Vec<ProductJson> = products
+            .iter()
+            .map(|p| ProductJson { id: 0, ..p.clone() })
+            .collect();
+        serde_json::to_value(products)
+    };
+    if without_ids(&current.products)? != without_ids(products)? {
+        return Ok(None);
+    }
+
+    let ids: Vec<i64> = products.iter().map(|p| p.id).collect();
+    let patched = match current.format {
+        Format::Toml => patch_toml(content, &ids),
+        Format::Yaml => patch_yaml(content, &ids),
+        Format::Json => None,
+    };
+
+    let Some(data) = patched else {
+        return Ok(None);
+    };
+    let reads_back = match parse_file(path, &data) {
+        Ok(file) => serde_json::to_value(&file.products)? == serde_json::to_value(products)?,
+        Err(_) => false,
+    };
+    Ok(reads_back.then_some(data))
+}
+
+/// Reads every entry of the catalog, in file order
+pub fn read_products(catalog: &str) -> Result<Vec<ProductJson>> {
+    let files = catalog_files(catalog)?;
+    if files.is_empty() {
+        if catalog == PRODUCT_FILE {
+            anyhow::bail!("Failed to open products.json. Did you run `jaxon init`?");
+        }
+        anyhow::bail!("No catalog files match {:?}", catalog);
+    }
+
+    let mut products = Vec::new();
+    for path in files {
+        products.extend(read_file(&path)?.products);
+    }
     Ok(products)
 }
 
-pub fn write_products(products: &[ProductJson]) -> Result<()> {
-    let data = serde_json::to_string_pretty(products).context("Failed to serialize data")?;
-    std::fs::write(PRODUCT_FILE, data).context("Failed to write products.json")?;
+/// Writes every entry back to the file it came from, keeping the format,
+/// layout and "$schema" of that file. Files whose entries did not change are
+/// left untouched, and TOML and YAML files where only IDs changed keep their
+/// comments.
+pub fn write_products(catalog: &str, products: &[ProductJson]) -> Result<()> {
+    let mut by_file: BTreeMap<PathBuf, Vec<(usize, ProductJson)>> = BTreeMap::new();
+    let mut new_entries = Vec::new();
+
+    for product in products {
+        match &product.source {
+            Some(source) => by_file
+                .entry(source.file.clone())
+                .or_default()
+                .push((source.index, product.clone())),
+            None => new_entries.push(product.clone()),
+        }
+    }
+    if !new_entries.is_empty() {
+        let entries = by_file.entry(default_file(catalog)?).or_default();
+        entries.extend(new_entries.into_iter().map(|p| (usize::MAX, p)));
+    }
+
+    for (path, mut entries) in by_file {
+        entries.sort_by_key(|(index, _)| *index);
+        let products: Vec<ProductJson> = entries.into_iter().map(|(_, p)| p).collect();
+
+        let content = if path.exists() {
+            Some(
+                std::fs::read_to_string(&path)
+                    .with_context(|| format!("Failed to read {}", path.display()))?,
+            )
+        } else {
+            None
+        };
+        let current = match &content {
+            Some(content) => Some(parse_file(&path, content)?),
+            None => None,
+        };
+        if let Some(current) = &current
+            && serde_json::to_value(&current.products)? == serde_json::to_value(&products)?
+        {
+            continue;
+        }
+
+        if let (Some(content), Some(current)) = (&content, &current)
+            && let Some(data) = patch_ids(&path, content, current, &products)?
+        {
+            files::write_atomic(&path, &data)?;
+            continue;
+        }
+
+        let file = match current {
+            Some(current) => CatalogFile {
+                products,
+                ..current
+            },
+            None => CatalogFile {
+                format: Format::of(&path).unwrap_or(Format::Json),
+                layout: None,
+                products,
+            },
+        };
+
+        let data = serialize_file(&file)
+            .with_context(|| format!("Failed to serialize {}", path.display()))?;
+        files::write_atomic(&path, &data)?;
+    }
+
     Ok(())
 }
 
@@ -42,29 +534,241 @@ pub fn init_product_json() -> Result<()> {
 
     let defaults = vec![
         ProductJson {
+            key: None,
             name: "Example Product".into(),
             description: "Example product's description".into(),
             product_type: "Product".into(),
             image: "assets/products/example.png".into(),
-            price: 499,
+            price: Some(499),
             regional_pricing: false,
             id: 0,
+            for_sale: true,
+            tags: Vec::new(),
+            metadata: BTreeMap::new(),
+            source: None,
+            shared_id: None,
         },
         ProductJson {
+            key: None,
             name: "Example Gamepass".into(),
             description: "Example gamepass's description".into(),
             product_type: "Gamepass".into(),
             image: "assets/gamepasses/example.png".into(),
-            price: 499,
+            price: Some(499),
             regional_pricing: false,
             id: 0,
+            for_sale: true,
+            tags: Vec::new(),
+            metadata: BTreeMap::new(),
+            source: None,
+            shared_id: None,
         },
     ];
 
+    let file = ProductFile::Document(ProductDocument {
+        schema: Some(format!("./{}", schema::PRODUCT_SCHEMA_FILE)),
+        products: defaults,
+    });
     let data =
-        serde_json::to_string_pretty(&defaults).context("Failed to serialize default products")?;
+        serde_json::to_string_pretty(&file).context("Failed to serialize default products")?;
     std::fs::write(PRODUCT_FILE, data).context("Failed to write products.json")?;
 
     println!("Created products.json");
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn try_patch(file: &str, content: &str, ids: &[i64]) -> Option<String> {
+        let path = Path::new(file);
+        let current = parse_file(path, content).unwrap();
+        let mut products = current.products.clone();
+        for (product, id) in products.iter_mut().zip(ids) {
+            product.id = *id;
+        }
+        patch_ids(path, content, &current, &products).unwrap()
+    }
+
+    fn patch(file: &str, content: &str, ids: &[i64]) -> String {
+        try_patch(file, content, ids).expect("only the IDs changed")
+    }
+
+    #[test]
+    fn toml_ids_keep_comments() {
+        let content = r#"#:schema ./products.schema.json
+# Lobby passes
+
+[[products]]
+name = "VIP" # shown in the shop
+description = ""
+type = "Gamepass"
+image = ""
+price = 100
+regionalPricing = false
+productId = 0 # set by jaxon
+"#;
+        assert_eq!(
+            patch("passes.toml", content, &[55]),
+            content.replace("productId = 0", "productId = 55")
+        );
+    }
+
+    #[test]
+    fn yaml_ids_keep_comments() {
+        let content = "# Currency packs
+- name: Gems   # keep short
+  description: ''
+  type: Product
+  image: ''
+  price: 5
+  regionalPricing: false
+  productId: 0   # set by jaxon
+  tags:
+    - currency
+- name: Gold
+  description: ''
+  type: Product
+  image: ''
+  price: 7
+  regionalPricing: false
+  productId: 0
+";
+        assert_eq!(
+            patch("packs.yaml", content, &[3, 4]),
+            content
+                .replacen("productId: 0", "productId: 3", 1)
+                .replacen("productId: 0", "productId: 4", 1)
+        );
+    }
+
+    #[test]
+    fn toml_entry_without_id_gets_one() {
+        let content = "[[products]]
+name = \"VIP\"
+description = \"\"
+type = \"Gamepass\"
+image = \"\"
+price = 100
+regionalPricing = false
+";
+        assert_eq!(
+            patch("passes.toml", content, &[8]),
+            format!("{}productId = 8\n", content)
+        );
+    }
+
+    #[test]
+    fn yaml_quoted_keys_are_patched() {
+        let content = "- \"name\": Gems
+  \"description\": ''
+  \"type\": Product
+  \"image\": ''
+  \"price\": 5
+  \"regionalPricing\": false
+  \"productId\": 0
+- 'productId': 0
+  'name': Gold
+  'description': ''
+  'type': Product
+  'image': ''
+  'price': 7
+  'regionalPricing': false
+";
+        assert_eq!(
+            patch("packs.yaml", content, &[3, 4]),
+            content
+                .replace("\"productId\": 0", "\"productId\": 3")
+                .replace("'productId': 0", "'productId': 4")
+        );
+    }
+
+    #[test]
+    fn yaml_entry_without_id_gets_one_after_nested_values() {
+        let content = "products:
+  - name: Gems
+    description: ''
+    type: Product
+    image: ''
+    price: 5
+    regionalPricing: false
+    metadata:
+      list:
+        - a
+  # Next pack
+  - name: Gold
+    description: ''
+    type: Product
+    image: ''
+    price: 7
+    regionalPricing: false
+";
+        assert_eq!(
+            patch("packs.yaml", content, &[3, 4]),
+            "products:
+  - name: Gems
+    description: ''
+    type: Product
+    image: ''
+    price: 5
+    regionalPricing: false
+    metadata:
+      list:
+        - a
+    productId: 3
+  # Next pack
+  - name: Gold
+    description: ''
+    type: Product
+    image: ''
+    price: 7
+    regionalPricing: false
+    productId: 4
+"
+        );
+    }
+
+    #[test]
+    fn yaml_flow_mappings_are_written_again() {
+        let content = "- {name: Gems, description: '', type: Product, image: '', price: 5, regionalPricing: false, productId: 0}
+";
+        assert_eq!(try_patch("packs.yaml", content, &[3]), None);
+    }
+
+    #[test]
+    fn yaml_with_several_documents_is_rejected() {
+        let content = "- name: Gems
+  description: ''
+  type: Product
+  image: ''
+  price: 5
+  regionalPricing: false
+---
+- name: Gold
+  description: ''
+  type: Product
+  image: ''
+  price: 7
+  regionalPricing: false
+";
+        assert!(parse_file(Path::new("packs.yaml"), content).is_err());
+    }
+
+    #[test]
+    fn glob_skips_files_that_are_not_catalogs() {
+        let dir = std::env::temp_dir().join(format!("jaxon-catalog-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        for file in ["a.json", "b.yml", "README.md"] {
+            std::fs::write(dir.join(file), "").unwrap();
+        }
+
+        let pattern = format!("{}/*", dir.display());
+        let names: Vec<String> = catalog_files(&pattern)
+            .unwrap()
+            .iter()
+            .map(|file| file.file_name().unwrap().to_string_lossy().into_owned())
+            .collect();
+        assert_eq!(names, ["a.json", "b.yml"]);
+    }
+}
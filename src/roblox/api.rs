@@ -1,41 +1,58 @@
 use anyhow::{Context, Result};
-use governor::{DefaultDirectRateLimiter, Quota, RateLimiter};
-use reqwest::blocking::multipart;
-use std::num::NonZeroU32;
+use reqwest::blocking::{Client, RequestBuilder, Response, multipart};
 use std::path::Path;
-use std::sync::OnceLock;
-use std::time::Duration;
 
+use super::limiter::AdaptiveLimiter;
+use super::retry::{self, RetryPolicy};
 use super::{
-    GamepassResponse, Product, ProductResponse, gamepass_info_url, gamepass_update_url,
-    gamepass_url, product_info_url, product_update_url, product_url,
+    ApiError, GamepassListResponse, GamepassResponse, Product, ProductListResponse,
+    ProductResponse, gamepass_info_url, gamepass_list_url, gamepass_update_url, gamepass_url,
+    product_info_url, product_list_url, product_update_url, product_url,
 };
 
-static RATE_LIMITER: OnceLock<DefaultDirectRateLimiter> = OnceLock::new();
+const LIST_PAGE_SIZE: u32 = 50;
 
-fn get_limiter() -> &'static DefaultDirectRateLimiter {
-    RATE_LIMITER.get_or_init(|| RateLimiter::direct(Quota::per_second(NonZeroU32::new(3).unwrap())))
+pub const DEFAULT_RATE: f64 = 3.0;
+
+pub const ROBLOX_API_URL: &str = "https://apis.roblox.com";
+
+/// Where requests go and how fast they are sent
+#[derive(Debug, Clone)]
+pub struct ApiOptions {
+    /// Base URL of an Open Cloud compatible server, such as `jaxon mock-server`
+    pub base_url: String,
+    /// Requests per second, shared by all workers
+    pub rate_limit: f64,
+    pub retry: RetryPolicy,
 }
 
-fn wait_for_token() {
-    let limiter = get_limiter();
-    while limiter.check().is_err() {
-        std::thread::sleep(Duration::from_millis(50));
-    }
+/// Open Cloud client for one API key. Every worker sharing it also shares its
+/// rate limit, so a 429 slows all of them down.
+pub struct OpenCloud {
+    base_url: String,
+    api_key: String,
+    client: Client,
+    limiter: AdaptiveLimiter,
+    policy: RetryPolicy,
 }
 
 fn build_form(data: &Product) -> Result<multipart::Form> {
     let mut form = multipart::Form::new()
         .text("name", data.name.clone())
         .text("description", data.description.clone())
-        .text("price", data.price.to_string())
-        .text("isForSale", "true")
+        .text("isForSale", data.for_sale.to_string())
         .text(
             "isRegionalPricingEnabled",
             data.regional_pricing.to_string(),
         );
+    if let Some(price) = data.price {
+        form = form.text("price", price.to_string());
+    }
 
-    if !data.image_file.is_empty() && Path::new(&data.image_file).exists() {
+    if !data.image_file.is_empty() {
+        if !Path::new(&data.image_file).exists() {
+            anyhow::bail!("Image file not found: {}", data.image_file);
+        }
         form = form
             .file("imageFile", &data.image_file)
             .with_context(|| format!("Failed to attach image file: {}", data.image_file))?;
@@ -44,137 +61,234 @@ fn build_form(data: &Product) -> Result<multipart::Form> {
     Ok(form)
 }
 
-// Retries on 429 with exponential backoff
-fn handle_response<T: serde::de::DeserializeOwned>(
-    make_request: impl Fn() -> Result<reqwest::blocking::Response>,
-) -> Result<T> {
-    let mut attempts = 0;
-
-    loop {
-        wait_for_token();
-
-        let resp = make_request()?;
-        let status = resp.status();
-
-        if status == reqwest::StatusCode::TOO_MANY_REQUESTS {
-            attempts += 1;
-            if attempts >= 5 {
-                anyhow::bail!(
-                    "Rate limit hit too many times, giving up after {} attempts",
-                    attempts
-                );
-            }
-            let wait = Duration::from_millis(1000 * attempts);
-            println!("Rate limited, retrying in {}ms...", wait.as_millis());
-            std::thread::sleep(wait);
-            continue;
-        }
+/// Page tokens are opaque and may contain `+`, `/` or `=`, so they are encoded
+fn page_url(base: &str, page_token: Option<&str>) -> Result<String> {
+    let page_size = LIST_PAGE_SIZE.to_string();
+    let mut params = vec![("pageSize", page_size.as_str())];
+    if let Some(token) = page_token {
+        params.push(("pageToken", token));
+    }
 
-        if !status.is_success() {
-            let body = resp.text().unwrap_or_default();
-            anyhow::bail!("Request failed ({}): {}", status, body);
-        }
+    let url = reqwest::Url::parse_with_params(base, &params)
+        .with_context(|| format!("Invalid API URL: {}", base))?;
+    Ok(url.into())
+}
+
+impl OpenCloud {
+    pub fn new(options: ApiOptions, api_key: String) -> Result<Self> {
+        let client = Client::builder()
+            .timeout(options.retry.timeout)
+            .build()
+            .context("Failed to build HTTP client")?;
 
-        return resp.json::<T>().context("Failed to decode response");
+        Ok(OpenCloud {
+            base_url: options.base_url.trim_end_matches('/').to_string(),
+            api_key,
+            client,
+            limiter: AdaptiveLimiter::new(options.rate_limit),
+            policy: options.retry,
+        })
     }
-}
 
-fn handle_response_no_body(
-    make_request: impl Fn() -> Result<reqwest::blocking::Response>,
-) -> Result<()> {
-    let mut attempts = 0;
+    /// Sends a request under the shared rate limit and retry policy.
+    ///
+    /// 429s slow down every worker through the limiter and honour `Retry-After`.
+    /// Transient server and network errors are retried with exponential backoff and
+    /// jitter, but only for idempotent requests (see `retry::is_transient_error`).
+    fn send(
+        &self,
+        make_request: impl Fn() -> Result<RequestBuilder>,
+        idempotent: bool,
+    ) -> Result<Response> {
+        let limiter = &self.limiter;
+        let mut attempt = 0;
 
-    loop {
-        wait_for_token();
+        loop {
+            attempt += 1;
+            limiter.acquire();
 
-        let resp = make_request()?;
-        let status = resp.status();
+            let (reason, delay) = match make_request()?.send() {
+                Ok(resp) if resp.status().is_success() => {
+                    limiter.on_success();
+                    return Ok(resp);
+                }
+                Ok(resp) if resp.status() == reqwest::StatusCode::TOO_MANY_REQUESTS => {
+                    let retry_after = retry::retry_after(resp.headers());
+                    let rate = limiter.on_rate_limited(retry_after);
+                    // The limiter already holds every worker back
+                    (
+                        format!("Rate limited, slowing down to {:.1} requests/s", rate),
+                        None,
+                    )
+                }
+                Ok(resp) if idempotent && retry::is_transient_status(resp.status()) => {
+                    let delay = retry::retry_after(resp.headers())
+                        .unwrap_or_else(|| retry::backoff(attempt));
+                    (format!("Request failed ({})", resp.status()), Some(delay))
+                }
+                Ok(resp) => {
+                    let status = resp.status();
+                    let message = resp.text().unwrap_or_default();
+                    return Err(ApiError { status, message }.into());
+                }
+                Err(e) if retry::is_transient_error(&e, idempotent) => (
+                    format!("Request failed ({})", e),
+                    Some(retry::backoff(attempt)),
+                ),
+                Err(e) => return Err(e).context("Request failed"),
+            };
 
-        if status == reqwest::StatusCode::TOO_MANY_REQUESTS {
-            attempts += 1;
-            if attempts >= 5 {
-                anyhow::bail!("Rate limited too many times after {} attempts", attempts);
+            if attempt >= self.policy.max_attempts {
+                anyhow::bail!("{}, giving up after {} attempts", reason, attempt);
             }
-            let wait = Duration::from_millis(1000 * attempts);
-            println!("Rate limited, retrying in {}ms...", wait.as_millis());
-            std::thread::sleep(wait);
-            continue;
-        }
 
-        if !status.is_success() {
-            let body = resp.text().unwrap_or_default();
-            anyhow::bail!("Request failed ({}): {}", status, body);
+            match delay {
+                Some(delay) => {
+                    println!("{}, retrying in {}ms...", reason, delay.as_millis());
+                    std::thread::sleep(delay);
+                }
+                None => println!("{}, retrying...", reason),
+            }
         }
+    }
 
-        return Ok(());
+    fn post<T: serde::de::DeserializeOwned>(&self, url: &str, data: &Product) -> Result<T> {
+        let resp = self.send(
+            || {
+                let form = build_form(data)?;
+                Ok(self
+                    .client
+                    .post(url)
+                    .header("x-api-key", &self.api_key)
+                    .multipart(form))
+            },
+            false,
+        )?;
+        resp.json::<T>().context("Failed to decode response")
     }
-}
 
-fn post<T: serde::de::DeserializeOwned>(url: &str, api_key: &str, data: &Product) -> Result<T> {
-    let client = reqwest::blocking::Client::new();
-    handle_response(|| {
-        let form = build_form(data)?;
-        client
-            .post(url)
-            .header("x-api-key", api_key)
-            .multipart(form)
-            .send()
-            .context("POST request failed")
-    })
-}
+    fn patch(&self, url: &str, data: &Product) -> Result<()> {
+        self.patch_form(url, || build_form(data))
+    }
 
-fn patch(url: &str, api_key: &str, data: &Product) -> Result<()> {
-    let client = reqwest::blocking::Client::new();
-    handle_response_no_body(|| {
-        let form = build_form(data)?;
-        client
-            .patch(url)
-            .header("x-api-key", api_key)
-            .multipart(form)
-            .send()
-            .context("PATCH request failed")
-    })
-}
+    fn patch_form(&self, url: &str, make_form: impl Fn() -> Result<multipart::Form>) -> Result<()> {
+        self.send(
+            || {
+                let form = make_form()?;
+                Ok(self
+                    .client
+                    .patch(url)
+                    .header("x-api-key", &self.api_key)
+                    .multipart(form))
+            },
+            true,
+        )?;
+        Ok(())
+    }
 
-fn get<T: serde::de::DeserializeOwned>(url: &str, api_key: &str) -> Result<T> {
-    let client = reqwest::blocking::Client::new();
-    handle_response(|| {
-        client
-            .get(url)
-            .header("x-api-key", api_key)
-            .send()
-            .context("GET request failed")
-    })
-}
+    fn get<T: serde::de::DeserializeOwned>(&self, url: &str) -> Result<T> {
+        let resp = self.send(
+            || Ok(self.client.get(url).header("x-api-key", &self.api_key)),
+            true,
+        )?;
+        resp.json::<T>().context("Failed to decode response")
+    }
 
-pub fn create_product(universe_id: i64, api_key: &str, data: &Product) -> Result<ProductResponse> {
-    post(&product_url(universe_id), api_key, data)
-}
+    pub fn create_product(&self, universe_id: i64, data: &Product) -> Result<ProductResponse> {
+        self.post(&product_url(&self.base_url, universe_id), data)
+    }
 
-pub fn create_gamepass(
-    universe_id: i64,
-    api_key: &str,
-    data: &Product,
-) -> Result<GamepassResponse> {
-    post(&gamepass_url(universe_id), api_key, data)
-}
+    pub fn create_gamepass(&self, universe_id: i64, data: &Product) -> Result<GamepassResponse> {
+        self.post(&gamepass_url(&self.base_url, universe_id), data)
+    }
+
+    pub fn update_product(
+        &self,
+        universe_id: i64,
+        id: i64,
+        data: &Product,
+    ) -> Result<ProductResponse> {
+        self.patch(&product_update_url(&self.base_url, universe_id, id), data)?;
+        self.get_product(universe_id, id)
+    }
+
+    pub fn update_gamepass(
+        &self,
+        universe_id: i64,
+        id: i64,
+        data: &Product,
+    ) -> Result<GamepassResponse> {
+        self.patch(&gamepass_update_url(&self.base_url, universe_id, id), data)?;
+        self.get_gamepass(universe_id, id)
+    }
+
+    pub fn set_product_for_sale(&self, universe_id: i64, id: i64, for_sale: bool) -> Result<()> {
+        self.patch_form(&product_update_url(&self.base_url, universe_id, id), || {
+            Ok(multipart::Form::new().text("isForSale", for_sale.to_string()))
+        })
+    }
+
+    pub fn set_gamepass_for_sale(&self, universe_id: i64, id: i64, for_sale: bool) -> Result<()> {
+        self.patch_form(
+            &gamepass_update_url(&self.base_url, universe_id, id),
+            || Ok(multipart::Form::new().text("isForSale", for_sale.to_string())),
+        )
+    }
 
-pub fn update_product(
-    universe_id: i64,
-    id: i64,
-    api_key: &str,
-    data: &Product,
-) -> Result<ProductResponse> {
-    patch(&product_update_url(universe_id, id), api_key, data)?;
-    get(&product_info_url(universe_id, id), api_key)
+    pub fn get_product(&self, universe_id: i64, id: i64) -> Result<ProductResponse> {
+        self.get(&product_info_url(&self.base_url, universe_id, id))
+    }
+
+    pub fn get_gamepass(&self, universe_id: i64, id: i64) -> Result<GamepassResponse> {
+        self.get(&gamepass_info_url(&self.base_url, universe_id, id))
+    }
+
+    pub fn list_products(&self, universe_id: i64) -> Result<Vec<ProductResponse>> {
+        let base = product_list_url(&self.base_url, universe_id);
+        let mut products = Vec::new();
+        let mut page_token: Option<String> = None;
+
+        loop {
+            let page: ProductListResponse = self.get(&page_url(&base, page_token.as_deref())?)?;
+            products.extend(page.developer_products);
+
+            match page.next_page_token {
+                Some(token) if !token.is_empty() => page_token = Some(token),
+                _ => break,
+            }
+        }
+
+        Ok(products)
+    }
+
+    pub fn list_gamepasses(&self, universe_id: i64) -> Result<Vec<GamepassResponse>> {
+        let base = gamepass_list_url(&self.base_url, universe_id);
+        let mut passes = Vec::new();
+        let mut page_token: Option<String> = None;
+
+        loop {
+            let page: GamepassListResponse = self.get(&page_url(&base, page_token.as_deref())?)?;
+            passes.extend(page.game_passes);
+
+            match page.next_page_token {
+                Some(token) if !token.is_empty() => page_token = Some(token),
+                _ => break,
+            }
+        }
+
+        Ok(passes)
+    }
 }
 
-pub fn update_gamepass(
-    universe_id: i64,
-    id: i64,
-    api_key: &str,
-    data: &Product,
-) -> Result<GamepassResponse> {
-    patch(&gamepass_update_url(universe_id, id), api_key, data)?;
-    get(&gamepass_info_url(universe_id, id), api_key)
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn page_url_encodes_token() {
+        assert_eq!(
+            page_url("https://apis.roblox.com/list", Some("a+b/c=")).unwrap(),
+            "https://apis.roblox.com/list?pageSize=50&pageToken=a%2Bb%2Fc%3D"
+        );
+    }
 }
@@ -0,0 +1,124 @@
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Lowest rate the limiter backs off to, in requests per second
+const MIN_RATE: f64 = 0.2;
+
+/// Share of the configured rate recovered after every successful request
+const RECOVERY_STEP: f64 = 0.05;
+
+/// Pause applied to every worker after a 429 without a usable `Retry-After`
+const RATE_LIMIT_PAUSE: Duration = Duration::from_secs(1);
+
+struct State {
+    rate: f64,
+    next_slot: Instant,
+}
+
+/// Rate limiter shared by all workers. It hands out evenly spaced request slots,
+/// halves the rate when Roblox answers with 429 and slowly recovers on success.
+pub struct AdaptiveLimiter {
+    max_rate: f64,
+    state: Mutex<State>,
+}
+
+impl AdaptiveLimiter {
+    pub fn new(requests_per_second: f64) -> Self {
+        let max_rate = requests_per_second.max(MIN_RATE);
+        AdaptiveLimiter {
+            max_rate,
+            state: Mutex::new(State {
+                rate: max_rate,
+                next_slot: Instant::now(),
+            }),
+        }
+    }
+
+    /// Blocks until the caller may send its next request
+    pub fn acquire(&self) {
+        let wait = {
+            let mut state = self.state.lock().unwrap();
+            let now = Instant::now();
+            let slot = state.next_slot.max(now);
+            state.next_slot = slot + Duration::from_secs_f64(1.0 / state.rate);
+            slot - now
+        };
+
+        if !wait.is_zero() {
+            std::thread::sleep(wait);
+        }
+    }
+
+    pub fn on_success(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.rate = (state.rate + self.max_rate * RECOVERY_STEP).min(self.max_rate);
+    }
+
+    /// Halves the rate and holds back every worker for `pause`. Returns the new rate.
+    pub fn on_rate_limited(&self, pause: Option<Duration>) -> f64 {
+        let mut state = self.state.lock().unwrap();
+        state.rate = (state.rate / 2.0).max(MIN_RATE);
+
+        let resume = Instant::now() + pause.unwrap_or(RATE_LIMIT_PAUSE);
+        state.next_slot = state.next_slot.max(resume);
+        state.rate
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rate(limiter: &AdaptiveLimiter) -> f64 {
+        limiter.state.lock().unwrap().rate
+    }
+
+    #[test]
+    fn rate_never_drops_below_the_floor() {
+        assert_eq!(rate(&AdaptiveLimiter::new(0.0)), MIN_RATE);
+
+        let limiter = AdaptiveLimiter::new(4.0);
+        assert_eq!(limiter.on_rate_limited(Some(Duration::ZERO)), 2.0);
+        for _ in 0..20 {
+            limiter.on_rate_limited(Some(Duration::ZERO));
+        }
+        assert_eq!(rate(&limiter), MIN_RATE);
+    }
+
+    #[test]
+    fn rate_recovers_up_to_the_configured_rate() {
+        let limiter = AdaptiveLimiter::new(4.0);
+        limiter.on_rate_limited(Some(Duration::ZERO));
+
+        // Every success recovers 5% of the configured rate, 0.2 here
+        limiter.on_success();
+        assert!((rate(&limiter) - 2.2).abs() < 1e-9);
+        for _ in 0..20 {
+            limiter.on_success();
+        }
+        assert_eq!(rate(&limiter), 4.0);
+    }
+
+    #[test]
+    fn rate_limit_holds_back_the_next_slot() {
+        let limiter = AdaptiveLimiter::new(100.0);
+        let before = Instant::now();
+        limiter.on_rate_limited(Some(Duration::from_secs(2)));
+        assert!(limiter.state.lock().unwrap().next_slot >= before + Duration::from_secs(2));
+
+        let limiter = AdaptiveLimiter::new(100.0);
+        limiter.on_rate_limited(None);
+        assert!(limiter.state.lock().unwrap().next_slot >= before + RATE_LIMIT_PAUSE);
+    }
+
+    #[test]
+    fn slots_are_spaced_by_the_rate() {
+        let limiter = AdaptiveLimiter::new(50.0);
+        let start = Instant::now();
+        for _ in 0..3 {
+            limiter.acquire();
+        }
+        // The first slot is immediate, the next two are 20ms apart
+        assert!(start.elapsed() >= Duration::from_millis(40));
+    }
+}
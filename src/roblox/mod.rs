@@ -1,20 +1,66 @@
 pub mod api;
+mod limiter;
+pub mod retry;
 
 use serde::{Deserialize, Serialize};
 
-pub use api::{create_gamepass, create_product, update_gamepass, update_product};
+use crate::products::{ProductKind, is_true};
 
-const ROBLOX_API_URL: &str = "https://apis.roblox.com";
+pub use api::{ApiOptions, OpenCloud};
+
+/// A request Roblox answered with an error status
+#[derive(Debug)]
+pub struct ApiError {
+    pub status: reqwest::StatusCode,
+    pub message: String,
+}
+
+impl std::fmt::Display for ApiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Request failed ({}): {}", self.status, self.message)
+    }
+}
+
+impl std::error::Error for ApiError {}
+
+/// Whether `error` says the entry does not exist, e.g. because it was deleted
+/// in the Creator Dashboard
+pub fn is_not_found(error: &anyhow::Error) -> bool {
+    error
+        .chain()
+        .filter_map(|e| e.downcast_ref::<ApiError>())
+        .any(|e| e.status == reqwest::StatusCode::NOT_FOUND)
+}
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Product {
     pub name: String,
     pub description: String,
-    #[serde(rename = "imageFile")]
+    /// Left out of the serialized form, images are hashed by content
+    #[serde(skip)]
     pub image_file: String,
-    pub price: i64,
+    /// Left out of the request when unset, which keeps the current price
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub price: Option<i64>,
     #[serde(rename = "isRegionalPricingEnabled")]
     pub regional_pricing: bool,
+    /// Skipped when true so hashes of entries without `forSale` stay the same
+    #[serde(rename = "isForSale", default, skip_serializing_if = "is_true")]
+    pub for_sale: bool,
+}
+
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct PriceInformation {
+    pub default_price_in_robux: Option<i64>,
+    #[serde(default)]
+    pub enabled_features: Vec<String>,
+}
+
+impl PriceInformation {
+    fn regional_pricing(&self) -> bool {
+        self.enabled_features.iter().any(|f| f == "RegionalPricing")
+    }
 }
 
 #[derive(Deserialize, Debug)]
@@ -22,7 +68,13 @@ pub struct Product {
 pub struct ProductResponse {
     pub product_id: i64,
     pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
     pub icon_image_asset_id: Option<i64>,
+    #[serde(default)]
+    pub price_information: Option<PriceInformation>,
+    #[serde(default)]
+    pub is_for_sale: Option<bool>,
 }
 
 #[derive(Deserialize, Debug)]
@@ -30,47 +82,130 @@ pub struct ProductResponse {
 pub struct GamepassResponse {
     pub game_pass_id: i64,
     pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    pub icon_asset_id: Option<i64>,
+    #[serde(default)]
+    pub price_information: Option<PriceInformation>,
+    #[serde(default)]
+    pub is_for_sale: Option<bool>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ProductListResponse {
+    #[serde(default)]
+    pub developer_products: Vec<ProductResponse>,
+    pub next_page_token: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct GamepassListResponse {
+    #[serde(default)]
+    pub game_passes: Vec<GamepassResponse>,
+    pub next_page_token: Option<String>,
+}
+
+/// Kind-agnostic view of a developer product or game pass as Roblox reports it
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteEntry {
+    pub kind: ProductKind,
+    pub id: i64,
+    pub name: String,
+    pub description: String,
+    /// Unset for entries that were never given a price, which are off sale
+    pub price: Option<i64>,
+    pub regional_pricing: bool,
+    pub for_sale: bool,
     pub icon_asset_id: Option<i64>,
 }
 
-pub fn gamepass_url(universe_id: i64) -> String {
+impl From<ProductResponse> for RemoteEntry {
+    fn from(info: ProductResponse) -> Self {
+        let price = info.price_information.unwrap_or_default();
+        RemoteEntry {
+            kind: ProductKind::Product,
+            id: info.product_id,
+            name: info.name,
+            description: info.description.unwrap_or_default(),
+            price: price.default_price_in_robux,
+            regional_pricing: price.regional_pricing(),
+            for_sale: info.is_for_sale.unwrap_or(true),
+            icon_asset_id: info.icon_image_asset_id,
+        }
+    }
+}
+
+impl From<GamepassResponse> for RemoteEntry {
+    fn from(info: GamepassResponse) -> Self {
+        let price = info.price_information.unwrap_or_default();
+        RemoteEntry {
+            kind: ProductKind::Gamepass,
+            id: info.game_pass_id,
+            name: info.name,
+            description: info.description.unwrap_or_default(),
+            price: price.default_price_in_robux,
+            regional_pricing: price.regional_pricing(),
+            for_sale: info.is_for_sale.unwrap_or(true),
+            icon_asset_id: info.icon_asset_id,
+        }
+    }
+}
+
+pub fn gamepass_url(base: &str, universe_id: i64) -> String {
     format!(
         "{}/game-passes/v1/universes/{}/game-passes",
-        ROBLOX_API_URL, universe_id
+        base, universe_id
     )
 }
 
-pub fn product_url(universe_id: i64) -> String {
+pub fn product_url(base: &str, universe_id: i64) -> String {
     format!(
         "{}/developer-products/v2/universes/{}/developer-products",
-        ROBLOX_API_URL, universe_id
+        base, universe_id
+    )
+}
+
+pub fn gamepass_list_url(base: &str, universe_id: i64) -> String {
+    format!(
+        "{}/game-passes/v1/universes/{}/game-passes/creator",
+        base, universe_id
+    )
+}
+
+pub fn product_list_url(base: &str, universe_id: i64) -> String {
+    format!(
+        "{}/developer-products/v2/universes/{}/developer-products/creator",
+        base, universe_id
     )
 }
 
-pub fn gamepass_update_url(universe_id: i64, id: i64) -> String {
+pub fn gamepass_update_url(base: &str, universe_id: i64, id: i64) -> String {
     format!(
         "{}/game-passes/v1/universes/{}/game-passes/{}",
-        ROBLOX_API_URL, universe_id, id
+        base, universe_id, id
     )
 }
 
-pub fn product_update_url(universe_id: i64, id: i64) -> String {
+pub fn product_update_url(base: &str, universe_id: i64, id: i64) -> String {
     format!(
         "{}/developer-products/v2/universes/{}/developer-products/{}",
-        ROBLOX_API_URL, universe_id, id
+        base, universe_id, id
     )
 }
 
-pub fn gamepass_info_url(universe_id: i64, id: i64) -> String {
+pub fn gamepass_info_url(base: &str, universe_id: i64, id: i64) -> String {
     format!(
         "{}/game-passes/v1/universes/{}/game-passes/{}/creator",
-        ROBLOX_API_URL, universe_id, id
+        base, universe_id, id
     )
 }
 
-pub fn product_info_url(universe_id: i64, id: i64) -> String {
+pub fn product_info_url(base: &str, universe_id: i64, id: i64) -> String {
     format!(
         "{}/developer-products/v2/universes/{}/developer-products/{}/creator",
-        ROBLOX_API_URL, universe_id, id
+        base, universe_id, id
     )
 }
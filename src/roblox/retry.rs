@@ -0,0 +1,162 @@
+use reqwest::StatusCode;
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use std::time::{Duration, SystemTime};
+
+const BASE_DELAY: Duration = Duration::from_millis(500);
+const MAX_DELAY: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+    /// Total attempts per request, including the first one
+    pub max_attempts: u32,
+    /// Timeout for a single attempt
+    pub timeout: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            max_attempts: 5,
+            timeout: Duration::from_secs(30),
+        }
+    }
+}
+
+/// Exponential backoff with full jitter for the given (1-based) attempt
+pub fn backoff(attempt: u32) -> Duration {
+    let exp = BASE_DELAY.saturating_mul(1 << attempt.saturating_sub(1).min(16));
+    let cap = exp.min(MAX_DELAY);
+    cap.mul_f64(fastrand::f64())
+}
+
+/// Reads `Retry-After` in either its delay-seconds or HTTP-date form
+pub fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let value = headers.get(RETRY_AFTER)?.to_str().ok()?.trim();
+
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds).min(MAX_DELAY));
+    }
+
+    let date = httpdate::parse_http_date(value).ok()?;
+    let delay = date
+        .duration_since(SystemTime::now())
+        .unwrap_or(Duration::ZERO);
+    Some(delay.min(MAX_DELAY))
+}
+
+pub fn is_transient_status(status: StatusCode) -> bool {
+    matches!(
+        status,
+        StatusCode::INTERNAL_SERVER_ERROR
+            | StatusCode::BAD_GATEWAY
+            | StatusCode::SERVICE_UNAVAILABLE
+            | StatusCode::GATEWAY_TIMEOUT
+    )
+}
+
+/// Whether a failed request can be sent again. Requests that are not idempotent
+/// are only retried when they never reached the server, so a create that timed
+/// out after Roblox processed it can't create a duplicate.
+pub fn is_transient_error(err: &reqwest::Error, idempotent: bool) -> bool {
+    if err.is_connect() {
+        return true;
+    }
+    idempotent && (err.is_timeout() || err.is_request())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+    use std::net::TcpListener;
+
+    fn headers(retry_after: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(RETRY_AFTER, HeaderValue::from_str(retry_after).unwrap());
+        headers
+    }
+
+    #[test]
+    fn retry_after_reads_seconds_and_dates() {
+        assert_eq!(retry_after(&headers("5")), Some(Duration::from_secs(5)));
+        assert_eq!(retry_after(&headers("600")), Some(MAX_DELAY));
+
+        let later = httpdate::fmt_http_date(SystemTime::now() + Duration::from_secs(10));
+        let delay = retry_after(&headers(&later)).unwrap();
+        assert!(delay > Duration::from_secs(8) && delay <= Duration::from_secs(10));
+
+        let past = httpdate::fmt_http_date(SystemTime::now() - Duration::from_secs(60));
+        assert_eq!(retry_after(&headers(&past)), Some(Duration::ZERO));
+
+        assert_eq!(retry_after(&headers("soon")), None);
+        assert_eq!(retry_after(&HeaderMap::new()), None);
+    }
+
+    #[test]
+    fn backoff_grows_up_to_the_cap() {
+        fastrand::seed(7);
+        for attempt in 1..=40 {
+            let cap = BASE_DELAY
+                .saturating_mul(1 << (attempt - 1).min(16))
+                .min(MAX_DELAY);
+            assert!(backoff(attempt) <= cap, "attempt {}", attempt);
+        }
+        assert!(backoff(u32::MAX) <= MAX_DELAY);
+    }
+
+    #[test]
+    fn backoff_is_jittered() {
+        fastrand::seed(7);
+        let delays: Vec<Duration> = (0..20).map(|_| backoff(4)).collect();
+        assert!(delays.iter().any(|d| *d != delays[0]));
+    }
+
+    #[test]
+    fn only_server_errors_are_transient() {
+        for status in [500, 502, 503, 504] {
+            assert!(is_transient_status(StatusCode::from_u16(status).unwrap()));
+        }
+        for status in [400, 401, 403, 404, 429, 501] {
+            assert!(!is_transient_status(StatusCode::from_u16(status).unwrap()));
+        }
+    }
+
+    fn client(timeout: Duration) -> reqwest::blocking::Client {
+        reqwest::blocking::Client::builder()
+            .timeout(timeout)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn refused_connections_are_retried_for_every_request() {
+        let port = TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        // The listener is dropped, so the connection is refused
+        let err = client(Duration::from_secs(5))
+            .post(format!("http://127.0.0.1:{}", port))
+            .send()
+            .unwrap_err();
+
+        assert!(is_transient_error(&err, true));
+        assert!(is_transient_error(&err, false));
+    }
+
+    #[test]
+    fn timeouts_are_only_retried_when_idempotent() {
+        // Connections are accepted by the OS, but never answered
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let err = client(Duration::from_millis(100))
+            .post(url)
+            .send()
+            .unwrap_err();
+
+        assert!(err.is_timeout());
+        assert!(is_transient_error(&err, true));
+        assert!(!is_transient_error(&err, false));
+    }
+}
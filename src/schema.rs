@@ -0,0 +1,41 @@
+use anyhow::{Context, Result};
+use std::path::Path;
+
+use crate::config::Config;
+use crate::products::ProductFile;
+
+pub const PRODUCT_SCHEMA_FILE: &str = "products.schema.json";
+pub const CONFIG_SCHEMA_FILE: &str = "jaxon.schema.json";
+
+fn write_schema(path: &Path, schema: &schemars::Schema) -> Result<()> {
+    let mut data = serde_json::to_string_pretty(schema).context("Failed to serialize schema")?;
+    data.push('\n');
+    std::fs::write(path, data).with_context(|| format!("Failed to write {}", path.display()))?;
+
+    println!("Wrote {}", path.display());
+    Ok(())
+}
+
+/// Writes the JSON Schema of products.json into `dir`
+pub fn write_product_schema(dir: &Path) -> Result<()> {
+    std::fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    write_schema(
+        &dir.join(PRODUCT_SCHEMA_FILE),
+        &schemars::schema_for!(ProductFile),
+    )
+}
+
+/// Writes the JSON Schema of jaxon.toml into `dir`
+pub fn write_config_schema(dir: &Path) -> Result<()> {
+    std::fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    write_schema(
+        &dir.join(CONFIG_SCHEMA_FILE),
+        &schemars::schema_for!(Config),
+    )
+}
+
+/// Writes the JSON Schemas of products.json and jaxon.toml into `dir`
+pub fn write_schemas(dir: &Path) -> Result<()> {
+    write_product_schema(dir)?;
+    write_config_schema(dir)
+}
@@ -0,0 +1,103 @@
+use anyhow::{Context, Result};
+use minijinja::{AutoEscape, Environment, context};
+use std::path::Path;
+
+use crate::code::{ProductCodeMap, luau_string, ts_string};
+use crate::config::{Config, Template};
+
+fn environment() -> Environment<'static> {
+    let mut env = Environment::new();
+    // Output is code, not HTML
+    env.set_auto_escape_callback(|_| AutoEscape::None);
+    env.set_keep_trailing_newline(true);
+    env.add_filter("luau_string", |value: String| luau_string(&value));
+    env.add_filter("ts_string", |value: String| ts_string(&value));
+    env
+}
+
+/// Renders every template with the whole catalog and writes the results
+pub fn render_all(
+    templates: &[Template],
+    products: &[ProductCodeMap],
+    passes: &[ProductCodeMap],
+    config: &Config,
+) -> Result<()> {
+    if templates.is_empty() {
+        return Ok(());
+    }
+
+    let env = environment();
+    let ctx = context! {
+        products => products,
+        gamepasses => passes,
+        universe_id => config.project.universe_id,
+        environment => config.environment,
+    };
+
+    for template in templates {
+        let source = std::fs::read_to_string(&template.template)
+            .with_context(|| format!("Failed to read template {}", template.template))?;
+        let rendered = env
+            .render_named_str(&template.template, &source, &ctx)
+            .with_context(|| format!("Failed to render template {}", template.template))?;
+
+        let output_path = Path::new(&template.output);
+        if let Some(parent) = output_path.parent()
+            && !parent.as_os_str().is_empty()
+        {
+            std::fs::create_dir_all(parent).with_context(|| {
+                format!("Failed to create directories for {}", output_path.display())
+            })?;
+        }
+        std::fs::write(output_path, rendered)
+            .with_context(|| format!("Failed to write {}", output_path.display()))?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::code::build_code_maps;
+    use crate::code::tests::{assert_golden, catalog};
+
+    const SHOP: &str = r#"-- Universe {{ universe_id }} ({{ environment }})
+local Shop = {
+{%- for product in products %}
+	[{{ product.key | luau_string }}] = { id = {{ product.id }}, name = {{ product.name | luau_string }}, price = {{ product.price }}, icon = {{ product.image | luau_string }} },
+{%- endfor %}
+}
+
+local Passes = {
+{%- for pass in gamepasses %}
+	[{{ pass.key | luau_string }}] = { id = {{ pass.id }}, forSale = {{ pass.for_sale | lower }} },
+{%- endfor %}
+}
+
+return { Shop = Shop, Passes = Passes }
+"#;
+
+    #[test]
+    fn template_matches_golden_file() {
+        let dir = std::env::temp_dir().join(format!("jaxon-template-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let source = dir.join("Shop.luau.j2");
+        std::fs::write(&source, SHOP).unwrap();
+        let output = dir.join("out/Shop.luau");
+
+        let (products, locked) = catalog();
+        let (products_map, passes_map) = build_code_maps(&products, &locked);
+        let mut config = Config::default();
+        config.project.universe_id = 42;
+        config.environment = Some("dev".into());
+        let templates = [Template {
+            template: source.to_string_lossy().into_owned(),
+            output: output.to_string_lossy().into_owned(),
+        }];
+        render_all(&templates, &products_map, &passes_map, &config).unwrap();
+
+        assert_golden("Shop.luau", &std::fs::read_to_string(&output).unwrap());
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,315 @@
+use anyhow::Result;
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::products::ProductJson;
+
+const MAX_NAME_LENGTH: usize = 50;
+const MAX_DESCRIPTION_LENGTH: usize = 1000;
+
+/// Allowed prices in Robux, the same for products and game passes
+const MIN_PRICE: i64 = 1;
+const MAX_PRICE: i64 = 1_000_000_000;
+
+/// Largest image Roblox accepts as an icon, in bytes
+const MAX_IMAGE_SIZE: u64 = 20 * 1024 * 1024;
+
+const IMAGE_EXTENSIONS: [&str; 5] = ["png", "jpg", "jpeg", "bmp", "tga"];
+
+/// Whether Roblox accepts `price` for a product or game pass
+pub fn is_valid_price(price: i64) -> bool {
+    (MIN_PRICE..=MAX_PRICE).contains(&price)
+}
+
+/// A mistake in products.json that Roblox would reject
+pub struct Problem {
+    /// Position of the entry in the catalog
+    pub index: usize,
+    /// Where the entry is defined, see `ProductJson::location`
+    pub location: String,
+    pub field: &'static str,
+    pub message: String,
+}
+
+impl std::fmt::Display for Problem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}: {}", self.location, self.field, self.message)
+    }
+}
+
+/// Checks that the file starts like an image of the format its extension claims
+fn matches_format(extension: &str, data: &[u8]) -> bool {
+    match extension {
+        "png" => data.starts_with(b"\x89PNG\r\n\x1a\n"),
+        "jpg" | "jpeg" => data.starts_with(&[0xFF, 0xD8, 0xFF]),
+        "bmp" => data.starts_with(b"BM"),
+        // TGA has no signature
+        _ => true,
+    }
+}
+
+fn check_image(path: &str) -> Option<String> {
+    let file = Path::new(path);
+    let extension = file
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_ascii_lowercase())
+        .unwrap_or_default();
+
+    if !IMAGE_EXTENSIONS.contains(&extension.as_str()) {
+        return Some(format!(
+            "{:?} is not a supported image format ({})",
+            path,
+            IMAGE_EXTENSIONS.join(", ")
+        ));
+    }
+
+    let data = match std::fs::read(file) {
+        Ok(data) => data,
+        Err(_) => return Some(format!("{:?} does not exist", path)),
+    };
+
+    if data.len() as u64 > MAX_IMAGE_SIZE {
+        return Some(format!(
+            "{:?} is {} bytes, the limit is {} bytes",
+            path,
+            data.len(),
+            MAX_IMAGE_SIZE
+        ));
+    }
+    if !matches_format(&extension, &data) {
+        return Some(format!("{:?} is not a valid {} file", path, extension));
+    }
+
+    None
+}
+
+/// Validates the whole catalog without contacting Roblox
+pub fn validate(products: &[ProductJson]) -> Vec<Problem> {
+    let mut problems = Vec::new();
+    let mut names: HashMap<(&str, &str), usize> = HashMap::new();
+    let mut keys: HashMap<(&str, &str), usize> = HashMap::new();
+    let mut ids: HashMap<i64, usize> = HashMap::new();
+
+    for (index, product) in products.iter().enumerate() {
+        let mut problem = |field: &'static str, message: String| {
+            problems.push(Problem {
+                index,
+                location: product.location(),
+                field,
+                message,
+            })
+        };
+
+        let name_length = product.name.chars().count();
+        if product.name.trim().is_empty() {
+            problem("name", "must not be empty".into());
+        } else if name_length > MAX_NAME_LENGTH {
+            problem(
+                "name",
+                format!(
+                    "is {} characters long, the limit is {}",
+                    name_length, MAX_NAME_LENGTH
+                ),
+            );
+        }
+
+        let description_length = product.description.chars().count();
+        if description_length > MAX_DESCRIPTION_LENGTH {
+            problem(
+                "description",
+                format!(
+                    "is {} characters long, the limit is {}",
+                    description_length, MAX_DESCRIPTION_LENGTH
+                ),
+            );
+        }
+
+        if let Some(key) = &product.key
+            && key.trim().is_empty()
+        {
+            problem("key", "must not be empty".into());
+        }
+
+        // Off-sale entries may have no price, or the placeholder 0 older pulls wrote
+        match product.price {
+            Some(price) if product.for_sale && !is_valid_price(price) => problem(
+                "price",
+                format!(
+                    "{} is out of range, prices are {} to {} Robux",
+                    price, MIN_PRICE, MAX_PRICE
+                ),
+            ),
+            None if product.for_sale => {
+                problem("price", "is required for entries that are for sale".into())
+            }
+            _ => {}
+        }
+
+        if product.kind().is_none() {
+            problem(
+                "type",
+                format!(
+                    "unknown type {:?}, expected \"Product\" or \"Gamepass\"",
+                    product.product_type
+                ),
+            );
+        }
+
+        if !product.image.is_empty()
+            && let Some(message) = check_image(&product.image)
+        {
+            problem("image", message);
+        }
+
+        let kind = product.product_type.as_str();
+        if let Some(first) = names.insert((kind, product.name.as_str()), index) {
+            problem(
+                "name",
+                format!(
+                    "{:?} is already used by {}",
+                    product.name,
+                    products[first].location()
+                ),
+            );
+        }
+        // Without explicit keys this is the same as a duplicate name
+        if let Some(first) = keys.insert((kind, product.key()), index)
+            && (product.key.is_some() || products[first].key.is_some())
+        {
+            problem(
+                "key",
+                format!(
+                    "{:?} is already used by {}",
+                    product.key(),
+                    products[first].location()
+                ),
+            );
+        }
+        if product.id > 0
+            && let Some(first) = ids.insert(product.id, index)
+        {
+            problem(
+                "productId",
+                format!(
+                    "{} is already used by {}",
+                    product.id,
+                    products[first].location()
+                ),
+            );
+        }
+    }
+
+    problems
+}
+
+/// Prints every problem in the catalog and fails when there is any
+pub fn check(products: &[ProductJson]) -> Result<()> {
+    report(&validate(products))
+}
+
+/// Prints `problems` and fails when there is any
+pub fn report(problems: &[Problem]) -> Result<()> {
+    if problems.is_empty() {
+        return Ok(());
+    }
+
+    for problem in problems {
+        println!("  ! {}", problem);
+    }
+    anyhow::bail!("Found {} problems in the catalog", problems.len());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fields(product: ProductJson) -> Vec<&'static str> {
+        validate(&[product]).iter().map(|p| p.field).collect()
+    }
+
+    /// Writes `data` to a fresh file named `name` in the temp directory
+    fn image(name: &str, data: &[u8]) -> String {
+        let dir = std::env::temp_dir().join(format!("jaxon-validate-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(name);
+        std::fs::write(&path, data).unwrap();
+        path.to_string_lossy().into_owned()
+    }
+
+    #[test]
+    fn name_and_description_limits() {
+        let at_limit = ProductJson {
+            description: "d".repeat(MAX_DESCRIPTION_LENGTH),
+            ..ProductJson::new(&"n".repeat(MAX_NAME_LENGTH), "Product")
+        };
+        assert!(fields(at_limit).is_empty());
+
+        let too_long = ProductJson {
+            description: "d".repeat(MAX_DESCRIPTION_LENGTH + 1),
+            ..ProductJson::new(&"é".repeat(MAX_NAME_LENGTH + 1), "Product")
+        };
+        assert_eq!(fields(too_long), ["name", "description"]);
+        assert_eq!(fields(ProductJson::new("  ", "Product")), ["name"]);
+    }
+
+    #[test]
+    fn price_range_is_the_same_for_every_type() {
+        for kind in ["Product", "Gamepass"] {
+            let priced = |price| ProductJson {
+                price: Some(price),
+                ..ProductJson::new("Coins", kind)
+            };
+            assert!(fields(priced(MIN_PRICE)).is_empty());
+            assert!(fields(priced(MAX_PRICE)).is_empty());
+            assert_eq!(fields(priced(MIN_PRICE - 1)), ["price"]);
+            assert_eq!(fields(priced(MAX_PRICE + 1)), ["price"]);
+        }
+    }
+
+    #[test]
+    fn off_sale_entries_need_no_price() {
+        let off_sale = |price| ProductJson {
+            price,
+            for_sale: false,
+            ..ProductJson::new("VIP", "Gamepass")
+        };
+        assert!(fields(off_sale(None)).is_empty());
+        assert!(fields(off_sale(Some(0))).is_empty());
+
+        let for_sale = ProductJson {
+            price: None,
+            ..ProductJson::new("VIP", "Gamepass")
+        };
+        assert_eq!(fields(for_sale), ["price"]);
+    }
+
+    #[test]
+    fn image_extension_and_size() {
+        let with_image = |image: String| ProductJson {
+            image,
+            ..ProductJson::new("Coins", "Product")
+        };
+
+        let png = image("icon.png", b"\x89PNG\r\n\x1a\n");
+        assert!(fields(with_image(png)).is_empty());
+
+        let gif = image("icon.gif", b"GIF89a");
+        assert_eq!(fields(with_image(gif)), ["image"]);
+
+        let mislabeled = image("icon.jpg", b"\x89PNG\r\n\x1a\n");
+        assert_eq!(fields(with_image(mislabeled)), ["image"]);
+
+        // Only the length is looked at before the signature, so a sparse file will do
+        let large = image("large.png", b"");
+        std::fs::OpenOptions::new()
+            .write(true)
+            .open(&large)
+            .unwrap()
+            .set_len(MAX_IMAGE_SIZE + 1)
+            .unwrap();
+        let problems = validate(&[with_image(large)]);
+        assert_eq!(problems.len(), 1);
+        assert!(problems[0].message.contains("the limit is"));
+    }
+}
@@ -0,0 +1,196 @@
+// Autogenerated by jaxon CLI
+
+declare const products: {
+	Products: {
+		"Back\\slash": {
+			Name: "Back\\slash";
+			Description: "";
+			Kind: "Product";
+			Id: 7;
+			Price: 10;
+			RegionalPricing: false;
+			ForSale: true;
+			Image: "rbxassetid://0";
+			IconAssetId: 0;
+			Tags: readonly [];
+			Metadata: {};
+		};
+		"Crème brûlée": {
+			Name: "Crème brûlée";
+			Description: "Rich \"custard\"\nwith caramel";
+			Kind: "Product";
+			Id: 5;
+			Price: 10;
+			RegionalPricing: true;
+			ForSale: true;
+			Image: "rbxassetid://0";
+			IconAssetId: 0;
+			Tags: readonly ["food", "limited"];
+			Metadata: { "category": "food"; "tags": readonly ["sweet", 2, null, { "a\"b": true; }]; };
+		};
+		"Zeta Pack": {
+			Name: "Zeta Pack";
+			Description: "";
+			Kind: "Product";
+			Id: 30;
+			Price: 10;
+			RegionalPricing: false;
+			ForSale: true;
+			Image: "rbxassetid://0";
+			IconAssetId: 0;
+			Tags: readonly [];
+			Metadata: {};
+		};
+		"multiline": {
+			Name: "Line\nbreak\ttab";
+			Description: "";
+			Kind: "Product";
+			Id: 21;
+			Price: 10;
+			RegionalPricing: false;
+			ForSale: true;
+			Image: "rbxassetid://0";
+			IconAssetId: 0;
+			Tags: readonly [];
+			Metadata: {};
+		};
+		"quote": {
+			Name: "The \"Best\" Deal";
+			Description: "";
+			Kind: "Product";
+			Id: 12;
+			Price: 10;
+			RegionalPricing: false;
+			ForSale: true;
+			Image: "rbxassetid://987";
+			IconAssetId: 987;
+			Tags: readonly [];
+			Metadata: {};
+		};
+	};
+	Gamepasses: {
+		"VIP": {
+			Name: "VIP";
+			Description: "";
+			Kind: "Gamepass";
+			Id: 400;
+			Price: 250;
+			RegionalPricing: false;
+			ForSale: false;
+			Image: "rbxassetid://0";
+			IconAssetId: 0;
+			Tags: readonly [];
+			Metadata: {};
+		};
+		"double": {
+			Name: "2x \"Coins\"";
+			Description: "";
+			Kind: "Gamepass";
+			Id: 100;
+			Price: 10;
+			RegionalPricing: false;
+			ForSale: true;
+			Image: "rbxassetid://0";
+			IconAssetId: 0;
+			Tags: readonly [];
+			Metadata: {};
+		};
+	};
+	ProductsById: {
+		5: {
+			Name: "Crème brûlée";
+			Description: "Rich \"custard\"\nwith caramel";
+			Kind: "Product";
+			Id: 5;
+			Price: 10;
+			RegionalPricing: true;
+			ForSale: true;
+			Image: "rbxassetid://0";
+			IconAssetId: 0;
+			Tags: readonly ["food", "limited"];
+			Metadata: { "category": "food"; "tags": readonly ["sweet", 2, null, { "a\"b": true; }]; };
+		};
+		7: {
+			Name: "Back\\slash";
+			Description: "";
+			Kind: "Product";
+			Id: 7;
+			Price: 10;
+			RegionalPricing: false;
+			ForSale: true;
+			Image: "rbxassetid://0";
+			IconAssetId: 0;
+			Tags: readonly [];
+			Metadata: {};
+		};
+		12: {
+			Name: "The \"Best\" Deal";
+			Description: "";
+			Kind: "Product";
+			Id: 12;
+			Price: 10;
+			RegionalPricing: false;
+			ForSale: true;
+			Image: "rbxassetid://987";
+			IconAssetId: 987;
+			Tags: readonly [];
+			Metadata: {};
+		};
+		21: {
+			Name: "Line\nbreak\ttab";
+			Description: "";
+			Kind: "Product";
+			Id: 21;
+			Price: 10;
+			RegionalPricing: false;
+			ForSale: true;
+			Image: "rbxassetid://0";
+			IconAssetId: 0;
+			Tags: readonly [];
+			Metadata: {};
+		};
+		30: {
+			Name: "Zeta Pack";
+			Description: "";
+			Kind: "Product";
+			Id: 30;
+			Price: 10;
+			RegionalPricing: false;
+			ForSale: true;
+			Image: "rbxassetid://0";
+			IconAssetId: 0;
+			Tags: readonly [];
+			Metadata: {};
+		};
+	};
+	GamepassesById: {
+		100: {
+			Name: "2x \"Coins\"";
+			Description: "";
+			Kind: "Gamepass";
+			Id: 100;
+			Price: 10;
+			RegionalPricing: false;
+			ForSale: true;
+			Image: "rbxassetid://0";
+			IconAssetId: 0;
+			Tags: readonly [];
+			Metadata: {};
+		};
+		400: {
+			Name: "VIP";
+			Description: "";
+			Kind: "Gamepass";
+			Id: 400;
+			Price: 250;
+			RegionalPricing: false;
+			ForSale: false;
+			Image: "rbxassetid://0";
+			IconAssetId: 0;
+			Tags: readonly [];
+			Metadata: {};
+		};
+	};
+};
+
+export = products;
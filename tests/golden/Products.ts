@@ -0,0 +1,114 @@
+// Autogenerated by jaxon CLI
+
+export const Products = {
+	"Back\\slash": {
+		Name: "Back\\slash",
+		Description: "",
+		Kind: "Product",
+		Id: 7,
+		Price: 10,
+		RegionalPricing: false,
+		ForSale: true,
+		Image: "rbxassetid://0",
+		IconAssetId: 0,
+		Tags: [],
+		Metadata: {},
+	},
+	"Crème brûlée": {
+		Name: "Crème brûlée",
+		Description: "Rich \"custard\"\nwith caramel",
+		Kind: "Product",
+		Id: 5,
+		Price: 10,
+		RegionalPricing: true,
+		ForSale: true,
+		Image: "rbxassetid://0",
+		IconAssetId: 0,
+		Tags: ["food", "limited"],
+		Metadata: { "category": "food", "tags": ["sweet", 2, null, { "a\"b": true }] },
+	},
+	"Zeta Pack": {
+		Name: "Zeta Pack",
+		Description: "",
+		Kind: "Product",
+		Id: 30,
+		Price: 10,
+		RegionalPricing: false,
+		ForSale: true,
+		Image: "rbxassetid://0",
+		IconAssetId: 0,
+		Tags: [],
+		Metadata: {},
+	},
+	"multiline": {
+		Name: "Line\nbreak\ttab",
+		Description: "",
+		Kind: "Product",
+		Id: 21,
+		Price: 10,
+		RegionalPricing: false,
+		ForSale: true,
+		Image: "rbxassetid://0",
+		IconAssetId: 0,
+		Tags: [],
+		Metadata: {},
+	},
+	"quote": {
+		Name: "The \"Best\" Deal",
+		Description: "",
+		Kind: "Product",
+		Id: 12,
+		Price: 10,
+		RegionalPricing: false,
+		ForSale: true,
+		Image: "rbxassetid://987",
+		IconAssetId: 987,
+		Tags: [],
+		Metadata: {},
+	},
+} as const;
+
+export const Gamepasses = {
+	"VIP": {
+		Name: "VIP",
+		Description: "",
+		Kind: "Gamepass",
+		Id: 400,
+		Price: 250,
+		RegionalPricing: false,
+		ForSale: false,
+		Image: "rbxassetid://0",
+		IconAssetId: 0,
+		Tags: [],
+		Metadata: {},
+	},
+	"double": {
+		Name: "2x \"Coins\"",
+		Description: "",
+		Kind: "Gamepass",
+		Id: 100,
+		Price: 10,
+		RegionalPricing: false,
+		ForSale: true,
+		Image: "rbxassetid://0",
+		IconAssetId: 0,
+		Tags: [],
+		Metadata: {},
+	},
+} as const;
+
+export const ProductsById = {
+	5: Products["Crème brûlée"],
+	7: Products["Back\\slash"],
+	12: Products["quote"],
+	21: Products["multiline"],
+	30: Products["Zeta Pack"],
+} as const;
+
+export const GamepassesById = {
+	100: Gamepasses["double"],
+	400: Gamepasses["VIP"],
+} as const;
+
+export type ProductName = keyof typeof Products;
+export type GamepassName = keyof typeof Gamepasses;
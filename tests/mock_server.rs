@@ -0,0 +1,159 @@
+//! Runs jaxon against its own mock server, so every request goes through the
+//! real HTTP backend, the multipart forms and the mock's parser.
+
+use std::net::{TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Output, Stdio};
+use std::time::{Duration, Instant};
+
+const JAXON: &str = env!("CARGO_BIN_EXE_jaxon");
+
+/// Mock server process, stopped when dropped
+struct MockServer {
+    child: Child,
+    url: String,
+}
+
+impl MockServer {
+    fn start(faults: &[&str]) -> Self {
+        let port = TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let child = Command::new(JAXON)
+            .args(["mock-server", "--port", &port.to_string()])
+            .args(faults)
+            .stdout(Stdio::null())
+            .spawn()
+            .unwrap();
+
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while TcpStream::connect(("127.0.0.1", port)).is_err() {
+            assert!(Instant::now() < deadline, "mock server did not start");
+            std::thread::sleep(Duration::from_millis(20));
+        }
+
+        MockServer {
+            child,
+            url: format!("http://127.0.0.1:{}", port),
+        }
+    }
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        self.child.kill().ok();
+        self.child.wait().ok();
+    }
+}
+
+/// Fresh project with one product and one game pass
+fn project(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("jaxon-it-{}-{}", name, std::process::id()));
+    std::fs::remove_dir_all(&dir).ok();
+    std::fs::create_dir_all(&dir).unwrap();
+
+    std::fs::write(
+        dir.join("jaxon.toml"),
+        "[project]\nuniverse_id = 42\n\n[files]\noutput = \"out/Products.luau\"\n",
+    )
+    .unwrap();
+    std::fs::write(
+        dir.join("products.json"),
+        r#"[
+  {"name": "Coins", "description": "A pile", "type": "Product", "image": "", "price": 10, "regionalPricing": false},
+  {"name": "VIP", "description": "", "type": "Gamepass", "image": "", "price": 200, "regionalPricing": true}
+]"#,
+    )
+    .unwrap();
+    dir
+}
+
+fn jaxon(dir: &Path, server: &MockServer, args: &[&str]) -> Output {
+    let output = Command::new(JAXON)
+        .args(args)
+        .current_dir(dir)
+        .env("JAXON_API_KEY", "test")
+        .env("JAXON_API_URL", &server.url)
+        .env("RUST_BACKTRACE", "0")
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "jaxon {:?} failed:\n{}{}",
+        args,
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    output
+}
+
+fn catalog(dir: &Path) -> Vec<serde_json::Value> {
+    let content = std::fs::read_to_string(dir.join("products.json")).unwrap();
+    serde_json::from_str(&content).unwrap()
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+#[test]
+fn create_update_and_list() {
+    let server = MockServer::start(&[]);
+    let dir = project("crud");
+
+    let synced = stdout(&jaxon(&dir, &server, &["sync"]));
+    assert!(
+        synced.contains("+ created  products.json[0] Product \"Coins\""),
+        "{}",
+        synced
+    );
+    let products = catalog(&dir);
+    assert!(products.iter().all(|p| p["productId"].as_i64() > Some(0)));
+
+    // Update the price, then check it against the live state
+    let content = std::fs::read_to_string(dir.join("products.json")).unwrap();
+    std::fs::write(
+        dir.join("products.json"),
+        content.replace("\"price\": 10", "\"price\": 15"),
+    )
+    .unwrap();
+    let plan = stdout(&jaxon(&dir, &server, &["sync", "--dry-run"]));
+    assert!(plan.contains("price: 10 -> 15"), "{}", plan);
+    let synced = stdout(&jaxon(&dir, &server, &["sync"]));
+    assert!(synced.contains("[metadata]"), "{}", synced);
+    let status = stdout(&jaxon(&dir, &server, &["status"]));
+    assert!(
+        status.contains("All 2 tracked entries match the live state"),
+        "{}",
+        status
+    );
+
+    // Listing finds both entries again by ID
+    let pulled = stdout(&jaxon(&dir, &server, &["pull"]));
+    assert!(
+        pulled.contains("Pulled 2 entries (0 new, 2 merged)"),
+        "{}",
+        pulled
+    );
+    assert_eq!(catalog(&dir)[0]["price"], 15);
+
+    std::fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn sync_survives_injected_faults() {
+    let server = MockServer::start(&["--fail-every", "3", "--rate-limit-every", "4"]);
+    let dir = project("faults");
+
+    jaxon(&dir, &server, &["sync", "--rate-limit", "50"]);
+    let status = stdout(&jaxon(&dir, &server, &["status"]));
+    assert!(
+        status.contains("All 2 tracked entries match the live state"),
+        "{}",
+        status
+    );
+
+    std::fs::remove_dir_all(&dir).ok();
+}